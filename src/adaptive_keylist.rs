@@ -0,0 +1,592 @@
+//! A `VecKeylist` that adds a hash index once it grows past a threshold.
+//!
+//! Small keylists stay a plain vector, so they don't pay for hashing. When the length goes over the threshold the
+//! index from key to positions is built, and after that `push`, `pop`, `insert` and `remove` keep it up to date, so
+//! `get` and `get_all` don't scan the list anymore. The index is dropped again when the keylist shrinks to half the
+//! threshold, so pushing and popping around the threshold does not rebuild it every time.
+//! ```
+//! use keylist::AdaptiveKeylist;
+//!
+//! let mut keylist = AdaptiveKeylist::with_threshold(2);
+//! keylist.push("a", 1);
+//! keylist.push("b", 2);
+//! assert!(!keylist.is_indexed());
+//!
+//! keylist.push("a", 3);
+//! assert!(keylist.is_indexed());
+//! assert_eq!(keylist.get(&"a"), Some(&1));
+//! assert_eq!(keylist.get_all(&"a"), vec![&1, &3]);
+//! ```
+//!
+//! Methods that hand out mutable keys (`iter_mut`, `keys_mut` and `get_key_value_mut`) drop the index, it is built
+//! again by the next call that changes the keylist.
+
+use crate::hash_keylist::HashMap;
+use crate::{KeylistSlice, VecKeylist};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::Deref;
+use core::slice;
+
+/// Length above which a keylist made with `AdaptiveKeylist::new` gets an index.
+pub const DEFAULT_THRESHOLD: usize = 16;
+
+pub struct AdaptiveKeylist<K, V> {
+    list: VecKeylist<K, V>,
+    /// positions in `list` of every key, in order
+    index: Option<HashMap<K, Vec<usize>>>,
+    threshold: usize,
+}
+
+impl<K, V> AdaptiveKeylist<K, V> {
+    pub fn new() -> Self {
+        AdaptiveKeylist::with_threshold(DEFAULT_THRESHOLD)
+    }
+
+    pub fn with_threshold(threshold: usize) -> Self {
+        AdaptiveKeylist {
+            list: VecKeylist::new(),
+            index: None,
+            threshold,
+        }
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.index.is_some()
+    }
+
+    pub fn into_inner(self) -> VecKeylist<K, V> {
+        self.list
+    }
+
+    /// Drops the index, because the keys can be changed.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, (K, V)> {
+        self.index = None;
+        self.list.iter_mut()
+    }
+
+    /// Drops the index, because the keys can be changed.
+    pub fn keys_mut(&mut self) -> impl Iterator<Item = &mut K> {
+        self.index = None;
+        self.list.keys_mut()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.list.values_mut()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> AdaptiveKeylist<K, V> {
+    fn build_index(&mut self) {
+        let mut index: HashMap<K, Vec<usize>> = HashMap::new();
+        for (i, (k, _)) in self.list.iter().enumerate() {
+            index.entry(k.clone()).or_default().push(i);
+        }
+        self.index = Some(index)
+    }
+
+    /// Builds or drops the index after the length changed.
+    fn adapt(&mut self) {
+        let len = self.list.len();
+        if len > self.threshold {
+            if self.index.is_none() {
+                self.build_index()
+            }
+        } else if len <= self.threshold / 2 {
+            self.index = None
+        }
+    }
+
+    fn positions(&self, key: &K) -> Option<&[usize]> {
+        match &self.index {
+            Some(index) => index.get(key).map(|positions| positions.as_slice()),
+            None => None,
+        }
+    }
+
+    pub fn into_swapped(self) -> AdaptiveKeylist<V, K>
+    where
+        V: Hash + Eq + Clone,
+    {
+        let mut swapped = AdaptiveKeylist {
+            list: self.list.into_swapped(),
+            index: None,
+            threshold: self.threshold,
+        };
+        swapped.adapt();
+        swapped
+    }
+
+    pub fn insert(&mut self, index: usize, k: K, v: V) {
+        if let Some(map) = &mut self.index {
+            for position in map.values_mut().flatten().filter(|p| **p >= index) {
+                *position += 1;
+            }
+            let positions = map.entry(k.clone()).or_default();
+            let at = positions.partition_point(|p| *p < index);
+            positions.insert(at, index);
+        }
+        self.list.insert(index, k, v);
+        self.adapt()
+    }
+
+    pub fn push(&mut self, k: K, v: V) {
+        let index = self.list.len();
+        if let Some(map) = &mut self.index {
+            map.entry(k.clone()).or_default().push(index);
+        }
+        self.list.push(k, v);
+        self.adapt()
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let (k, v) = self.list.pop()?;
+        if let Some(map) = &mut self.index {
+            let positions = map.get_mut(&k).expect("popped key is indexed");
+            positions.pop();
+            if positions.is_empty() {
+                map.remove(&k);
+            }
+        }
+        self.adapt();
+        Some((k, v))
+    }
+
+    pub fn remove(&mut self, index: usize) -> (K, V) {
+        let (k, v) = self.list.remove(index);
+        if let Some(map) = &mut self.index {
+            let positions = map.get_mut(&k).expect("removed key is indexed");
+            positions.retain(|p| *p != index);
+            if positions.is_empty() {
+                map.remove(&k);
+            }
+            for position in map.values_mut().flatten().filter(|p| **p > index) {
+                *position -= 1;
+            }
+        }
+        self.adapt();
+        (k, v)
+    }
+
+    pub fn get_key_value(&self, key: &K) -> Option<&(K, V)> {
+        if self.index.is_none() {
+            return self.list.get_key_value(key);
+        }
+        let positions = self.positions(key)?;
+        Some(&self.list.0[positions[0]])
+    }
+
+    /// Drops the index, because the key can be changed.
+    pub fn get_key_value_mut(&mut self, key: &K) -> Option<&mut (K, V)> {
+        let position = match self.positions(key) {
+            Some(positions) => positions[0],
+            None if self.index.is_some() => return None,
+            None => return self.list.get_key_value_mut(key),
+        };
+        self.index = None;
+        Some(&mut self.list.0[position])
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let (_, v) = self.get_key_value(key)?;
+        Some(v)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        if self.index.is_none() {
+            return self.list.get_mut(key);
+        }
+        let position = self.positions(key)?[0];
+        Some(&mut self.list.0[position].1)
+    }
+
+    pub fn get_all_get_key_value(&self, key: &K) -> Vec<&(K, V)> {
+        if self.index.is_none() {
+            return self.list.get_all_get_key_value(key);
+        }
+        self.positions(key)
+            .unwrap_or_default()
+            .iter()
+            .map(|p| &self.list.0[*p])
+            .collect()
+    }
+
+    /// get all values matching the key
+    pub fn get_all(&self, key: &K) -> Vec<&V> {
+        self.get_all_get_key_value(key)
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get_key_value(key).is_some()
+    }
+
+    pub fn contains(&self, item: &(K, V)) -> bool
+    where
+        V: PartialEq,
+    {
+        self.get_all_get_key_value(&item.0)
+            .into_iter()
+            .any(|(_, v)| *v == item.1)
+    }
+
+    /// Rebuilds the index if there is one, the positions all changed.
+    fn reindex(&mut self) {
+        self.index = None;
+        self.adapt()
+    }
+}
+
+impl<K: Hash + Eq + Clone + Ord, V> AdaptiveKeylist<K, V> {
+    pub fn sort_by_key(&mut self) {
+        self.list.sort_by_key();
+        self.reindex()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Ord> AdaptiveKeylist<K, V> {
+    pub fn sort_by_value(&mut self) {
+        self.list.sort_by_value();
+        self.reindex()
+    }
+}
+
+impl<K: Hash + Eq + Clone + Ord, V: Ord> AdaptiveKeylist<K, V> {
+    pub fn sort(&mut self) {
+        self.list.sort();
+        self.reindex()
+    }
+}
+
+/// The read only methods of `VecKeylist` that don't need the index.
+impl<K, V> Deref for AdaptiveKeylist<K, V> {
+    type Target = KeylistSlice<K, V>;
+
+    fn deref(&self) -> &KeylistSlice<K, V> {
+        &self.list
+    }
+}
+
+impl<K, V> Default for AdaptiveKeylist<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for AdaptiveKeylist<K, V> {
+    fn clone(&self) -> Self {
+        AdaptiveKeylist {
+            list: self.list.clone(),
+            index: self.index.clone(),
+            threshold: self.threshold,
+        }
+    }
+}
+
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for AdaptiveKeylist<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.list.iter()).finish()
+    }
+}
+
+/// Compares the pairs, the threshold and index don't matter.
+impl<K: PartialEq, V: PartialEq> PartialEq for AdaptiveKeylist<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.list == other.list
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for AdaptiveKeylist<K, V> {}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<VecKeylist<K, V>> for AdaptiveKeylist<K, V> {
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        self.list == *other
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<AdaptiveKeylist<K, V>> for VecKeylist<K, V> {
+    fn eq(&self, other: &AdaptiveKeylist<K, V>) -> bool {
+        *self == other.list
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for AdaptiveKeylist<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.list.hash(state);
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> From<VecKeylist<K, V>> for AdaptiveKeylist<K, V> {
+    fn from(list: VecKeylist<K, V>) -> Self {
+        let mut keylist = AdaptiveKeylist {
+            list,
+            index: None,
+            threshold: DEFAULT_THRESHOLD,
+        };
+        keylist.adapt();
+        keylist
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> From<Vec<(K, V)>> for AdaptiveKeylist<K, V> {
+    fn from(list: Vec<(K, V)>) -> Self {
+        AdaptiveKeylist::from(VecKeylist(list))
+    }
+}
+
+impl<K: Hash + Eq + Clone, V, const N: usize> From<[(K, V); N]> for AdaptiveKeylist<K, V> {
+    fn from(list: [(K, V); N]) -> Self {
+        AdaptiveKeylist::from(VecKeylist::from(list))
+    }
+}
+
+impl<K, V> From<AdaptiveKeylist<K, V>> for VecKeylist<K, V> {
+    fn from(keylist: AdaptiveKeylist<K, V>) -> Self {
+        keylist.list
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Extend<(K, V)> for AdaptiveKeylist<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.push(k, v)
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> FromIterator<(K, V)> for AdaptiveKeylist<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        AdaptiveKeylist::from(VecKeylist::from_iter(iter))
+    }
+}
+
+impl<K, V> IntoIterator for AdaptiveKeylist<K, V> {
+    type Item = (K, V);
+    type IntoIter = alloc::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a AdaptiveKeylist<K, V> {
+    type Item = &'a (K, V);
+    type IntoIter = slice::Iter<'a, (K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use crate::{AdaptiveKeylist, VecKeylist};
+    use core::hash::Hash;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<K: Serialize, V: Serialize> Serialize for AdaptiveKeylist<K, V> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_newtype_struct("AdaptiveKeylist", &self.list.0)
+        }
+    }
+
+    /// Accepts the same input as `VecKeylist`: a list of pairs or a map.
+    impl<'de, K, V> Deserialize<'de> for AdaptiveKeylist<K, V>
+    where
+        K: Deserialize<'de> + Hash + Eq + Clone,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let keylist: VecKeylist<K, V> = Deserialize::deserialize(deserializer)?;
+            Ok(AdaptiveKeylist::from(keylist))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AdaptiveKeylist, VecKeylist};
+
+    const KEYS: u32 = 12;
+
+    /// Checks every lookup against the same pairs in a `VecKeylist`.
+    fn assert_same(keylist: &AdaptiveKeylist<u32, u32>, expected: &VecKeylist<u32, u32>) {
+        assert_eq!(keylist, expected);
+        assert_eq!(keylist.len(), expected.len());
+        for key in 0..KEYS {
+            assert_eq!(keylist.get(&key), expected.get(&key));
+            assert_eq!(keylist.get_key_value(&key), expected.get_key_value(&key));
+            assert_eq!(keylist.get_all(&key), expected.get_all(&key));
+            assert_eq!(
+                keylist.get_all_get_key_value(&key),
+                expected.get_all_get_key_value(&key)
+            );
+            assert_eq!(keylist.contains_key(&key), expected.get(&key).is_some());
+            assert_eq!(keylist.contains(&(key, 3)), expected.contains(&(key, 3)));
+        }
+    }
+
+    /// Runs the same operations on keylists with different thresholds and on a `VecKeylist`.
+    fn run(threshold: usize) {
+        let mut keylist = AdaptiveKeylist::with_threshold(threshold);
+        let mut expected = VecKeylist::new();
+        let mut state = 7u32;
+        let mut indexed = false;
+
+        for step in 0..400 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let (key, value) = ((state >> 8) % KEYS, (state >> 16) % 5);
+            match (state >> 4) % 8 {
+                0..=2 => {
+                    keylist.push(key, value);
+                    expected.push(key, value);
+                }
+                3 if !expected.is_empty() => {
+                    let index = value as usize % expected.len();
+                    assert_eq!(keylist.remove(index), expected.remove(index));
+                }
+                4 => assert_eq!(keylist.pop(), expected.pop()),
+                5 => {
+                    let index = value as usize % (expected.len() + 1);
+                    keylist.insert(index, key, value);
+                    expected.insert(index, key, value);
+                }
+                6 if step % 5 == 0 => {
+                    keylist.sort_by_value();
+                    expected.sort_by_value();
+                }
+                6 => {
+                    if let Some(v) = keylist.get_mut(&key) {
+                        *v += 1;
+                    }
+                    if let Some(v) = expected.get_mut(&key) {
+                        *v += 1;
+                    }
+                }
+                _ => {
+                    keylist.extend(vec![(key, value), (value, key)]);
+                    expected.extend(vec![(key, value), (value, key)]);
+                }
+            }
+            indexed |= keylist.is_indexed();
+            assert_same(&keylist, &expected);
+        }
+
+        assert_eq!(indexed, threshold < 400);
+    }
+
+    #[test]
+    fn same_as_vec_keylist() {
+        run(0);
+        run(4);
+        run(16);
+        run(usize::MAX);
+    }
+
+    #[test]
+    fn threshold() {
+        let mut keylist = AdaptiveKeylist::with_threshold(4);
+
+        for i in 0..4 {
+            keylist.push(i, i);
+        }
+        assert!(!keylist.is_indexed());
+        keylist.push(4, 4);
+        assert!(keylist.is_indexed());
+
+        keylist.pop();
+        keylist.pop();
+        assert!(keylist.is_indexed());
+        keylist.pop();
+        assert!(!keylist.is_indexed());
+    }
+
+    #[test]
+    fn mutable_keys() {
+        let mut keylist = AdaptiveKeylist::with_threshold(1);
+        keylist.extend(vec![("a", 1), ("b", 2), ("a", 3)]);
+        assert!(keylist.is_indexed());
+
+        for key in keylist.keys_mut() {
+            if *key == "a" {
+                *key = "c"
+            }
+        }
+        assert!(!keylist.is_indexed());
+        assert_eq!(keylist.get_all(&"c"), vec![&1, &3]);
+        assert_eq!(keylist.get(&"a"), None);
+
+        keylist.push("a", 4);
+        assert!(keylist.is_indexed());
+        assert_eq!(keylist.get_all(&"c"), vec![&1, &3]);
+
+        keylist.get_key_value_mut(&"b").unwrap().0 = "a";
+        keylist.sort_by_key();
+        assert!(keylist.is_indexed());
+        assert_eq!(keylist.get_all(&"a"), vec![&2, &4]);
+    }
+
+    #[test]
+    fn conversions() {
+        let pairs: Vec<_> = (0..20).map(|i| (i % 3, i)).collect();
+        let keylist = AdaptiveKeylist::from(pairs.clone());
+
+        assert!(keylist.is_indexed());
+        assert_eq!(keylist.get_all(&2).len(), 6);
+        assert_eq!(keylist[..3].get(&2), Some(&2));
+        assert_eq!(VecKeylist::from(keylist.clone()), VecKeylist(pairs));
+
+        let swapped = keylist.into_swapped();
+        assert_eq!(swapped.get(&5), Some(&2));
+
+        let small = AdaptiveKeylist::from([("a", 1)]);
+        assert!(!small.is_indexed());
+        assert_eq!(format!("{:?}", small), r#"[("a", 1)]"#);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use crate::AdaptiveKeylist;
+    use serde_test::{assert_tokens, Token};
+
+    #[test]
+    fn serde_tokens() {
+        let keylist = AdaptiveKeylist::from(vec![("oke", 1), ("test", 15)]);
+
+        assert_tokens(
+            &keylist,
+            &[
+                Token::NewtypeStruct {
+                    name: "AdaptiveKeylist",
+                },
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
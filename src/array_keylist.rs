@@ -0,0 +1,441 @@
+//! A keylist with a fixed capacity of `N` pairs that never allocates.
+//!
+//! Needs the `heapless` feature, but not `alloc`. It derefs to `KeylistSlice` like `VecKeylist`, except that adding
+//! pairs can fail when the keylist is full and that `get_all` returns an iterator instead of a `Vec`. `new` is a
+//! `const fn`, so it can be used for `static` items.
+//! ```
+//! use keylist::ArrayKeylist;
+//!
+//! static EMPTY: ArrayKeylist<&str, u8, 4> = ArrayKeylist::new();
+//!
+//! let mut keylist: ArrayKeylist<&str, u8, 2> = ArrayKeylist::new();
+//! assert_eq!(keylist.try_push("a", 1), Ok(()));
+//! assert_eq!(keylist.try_push("a", 2), Ok(()));
+//! assert_eq!(keylist.try_push("b", 3), Err(("b", 3)));
+//!
+//! assert_eq!(keylist.get(&"a"), Some(&1));
+//! assert_eq!(keylist.get_all(&"a").collect::<Vec<_>>(), vec![&1, &2]);
+//! assert!(EMPTY.is_empty());
+//! ```
+
+use crate::KeylistSlice;
+#[cfg(feature = "alloc")]
+use crate::VecKeylist;
+#[cfg(feature = "alloc")]
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use heapless::Vec;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArrayKeylist<K, V, const N: usize>(pub Vec<(K, V), N>);
+
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<K: fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug for ArrayKeylist<K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, const N: usize> Default for ArrayKeylist<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> ArrayKeylist<K, V, N> {
+    pub const fn new() -> Self {
+        ArrayKeylist(Vec::new())
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.0.is_full()
+    }
+
+    pub fn into_swapped(self) -> ArrayKeylist<V, K, N> {
+        ArrayKeylist(self.0.into_iter().map(|(k, v)| (v, k)).collect())
+    }
+
+    /// Returns the pair back if the keylist is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn try_insert(&mut self, index: usize, k: K, v: V) -> Result<(), (K, V)> {
+        self.0.insert(index, (k, v))
+    }
+
+    /// Returns the pair back if the keylist is full.
+    pub fn try_push(&mut self, k: K, v: V) -> Result<(), (K, V)> {
+        self.0.push((k, v))
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        self.0.pop()
+    }
+
+    pub fn remove(&mut self, index: usize) -> (K, V) {
+        self.0.remove(index)
+    }
+}
+
+impl<K: PartialEq, V, const N: usize> ArrayKeylist<K, V, N> {
+    /// get all values matching the key, as an iterator so it does not allocate
+    pub fn get_all<'a>(&'a self, key: &'a K) -> impl Iterator<Item = &'a V> {
+        self.iter().filter(move |(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+impl<K, V, const N: usize> Deref for ArrayKeylist<K, V, N> {
+    type Target = KeylistSlice<K, V>;
+
+    fn deref(&self) -> &KeylistSlice<K, V> {
+        KeylistSlice::new(&self.0)
+    }
+}
+
+impl<K, V, const N: usize> DerefMut for ArrayKeylist<K, V, N> {
+    fn deref_mut(&mut self) -> &mut KeylistSlice<K, V> {
+        KeylistSlice::new_mut(&mut self.0)
+    }
+}
+
+impl<K: Clone, V: Clone, const N: usize> ArrayKeylist<K, V, N> {
+    /// Returns `None` if the slice does not fit.
+    pub fn from_slice(pairs: &[(K, V)]) -> Option<Self> {
+        Some(ArrayKeylist(Vec::from_slice(pairs).ok()?))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V, const N: usize> TryFrom<VecKeylist<K, V>> for ArrayKeylist<K, V, N> {
+    type Error = VecKeylist<K, V>;
+
+    /// Gives the keylist back if it does not fit.
+    fn try_from(list: VecKeylist<K, V>) -> Result<Self, Self::Error> {
+        if list.len() > N {
+            return Err(list);
+        }
+        Ok(ArrayKeylist(list.0.into_iter().collect()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V, const N: usize> From<ArrayKeylist<K, V, N>> for VecKeylist<K, V> {
+    fn from(list: ArrayKeylist<K, V, N>) -> Self {
+        VecKeylist(list.0.into_iter().collect())
+    }
+}
+
+impl<K, V, const N: usize> From<[(K, V); N]> for ArrayKeylist<K, V, N> {
+    /// Always fits, the capacity is the length of the array.
+    fn from(list: [(K, V); N]) -> Self {
+        ArrayKeylist(IntoIterator::into_iter(list).collect())
+    }
+}
+
+impl<K, V, const N: usize> IntoIterator for ArrayKeylist<K, V, N> {
+    type Item = (K, V);
+    type IntoIter = <Vec<(K, V), N> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, const N: usize> PartialEq<[(K, V)]> for ArrayKeylist<K, V, N> {
+    fn eq(&self, other: &[(K, V)]) -> bool {
+        self.0[..] == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: PartialEq, V: PartialEq, const N: usize> PartialEq<VecKeylist<K, V>>
+    for ArrayKeylist<K, V, N>
+{
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: PartialEq, V: PartialEq, const N: usize> PartialEq<ArrayKeylist<K, V, N>>
+    for VecKeylist<K, V>
+{
+    fn eq(&self, other: &ArrayKeylist<K, V, N>) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use crate::ArrayKeylist;
+    use core::marker::PhantomData;
+    use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<K: Serialize, V: Serialize, const N: usize> Serialize for ArrayKeylist<K, V, N> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_newtype_struct("ArrayKeylist", &self.0[..])
+        }
+    }
+
+    struct KeylistVisitor<K, V, const N: usize> {
+        marker: PhantomData<fn() -> ArrayKeylist<K, V, N>>,
+    }
+
+    impl<K, V, const N: usize> KeylistVisitor<K, V, N> {
+        fn new() -> Self {
+            KeylistVisitor {
+                marker: PhantomData,
+            }
+        }
+    }
+
+    impl<'de, K, V, const N: usize> Visitor<'de> for KeylistVisitor<K, V, N>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        type Value = ArrayKeylist<K, V, N>;
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(formatter, "Struct ArrayKeylist with at most {} pairs", N)
+        }
+
+        fn visit_seq<X>(self, mut seq: X) -> Result<Self::Value, X::Error>
+        where
+            X: SeqAccess<'de>,
+        {
+            let mut keylist = ArrayKeylist::new();
+
+            while let Some((k, v)) = seq.next_element()? {
+                if keylist.try_push(k, v).is_err() {
+                    return Err(X::Error::invalid_length(N + 1, &self));
+                }
+            }
+            Ok(keylist)
+        }
+
+        fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let mut keylist = ArrayKeylist::new();
+
+            while let Some((k, v)) = access.next_entry()? {
+                if keylist.try_push(k, v).is_err() {
+                    return Err(M::Error::invalid_length(N + 1, &self));
+                }
+            }
+            Ok(keylist)
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_newtype_struct("ArrayKeylist", KeylistVisitor::new())
+        }
+    }
+
+    impl<'de, K, V, const N: usize> Deserialize<'de> for ArrayKeylist<K, V, N>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(KeylistVisitor::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ArrayKeylist;
+
+    static TABLE: ArrayKeylist<&str, u8, 4> = ArrayKeylist::new();
+
+    fn data() -> ArrayKeylist<&'static str, u8, 4> {
+        ArrayKeylist::from_slice(&[("b", 2), ("a", 5), ("b", 1)]).unwrap()
+    }
+
+    #[test]
+    fn capacity() {
+        let mut keylist = data();
+
+        assert_eq!(keylist.capacity(), 4);
+        assert!(!keylist.is_full());
+        assert_eq!(keylist.try_push("c", 3), Ok(()));
+        assert!(keylist.is_full());
+        assert_eq!(keylist.try_push("d", 4), Err(("d", 4)));
+        assert_eq!(keylist.try_insert(0, "d", 4), Err(("d", 4)));
+        assert_eq!(keylist.len(), 4);
+
+        assert_eq!(keylist.pop(), Some(("c", 3)));
+        assert_eq!(keylist.try_insert(0, "d", 4), Ok(()));
+        assert_eq!(keylist.remove(0), ("d", 4));
+
+        assert!(ArrayKeylist::<u8, u8, 1>::from_slice(&[(1, 1), (2, 2)]).is_none());
+        assert!(TABLE.is_empty());
+    }
+
+    #[test]
+    fn get() {
+        let mut keylist = data();
+
+        assert_eq!(keylist.get(&"b"), Some(&2));
+        assert_eq!(keylist.get_all(&"b").collect::<Vec<_>>(), vec![&2, &1]);
+        assert_eq!(keylist.get_key_value(&"a"), Some(&("a", 5)));
+        assert_eq!(keylist.find_by_value(&1), Some(&("b", 1)));
+        assert!(keylist.contains(&("a", 5)));
+        assert_eq!(keylist.get(&"c"), None);
+
+        *keylist.get_mut(&"a").unwrap() = 6;
+        for v in keylist.values_mut() {
+            *v += 1;
+        }
+        assert_eq!(keylist.values().collect::<Vec<_>>(), vec![&3, &7, &2]);
+    }
+
+    #[test]
+    fn sort() {
+        let mut keylist = data();
+
+        keylist.sort_by_key();
+        assert!(keylist == [("a", 5), ("b", 2), ("b", 1)][..]);
+        assert_eq!(keylist.get_sorted(&"b"), Some(&2));
+
+        keylist.sort_by_value();
+        assert!(keylist == [("b", 1), ("b", 2), ("a", 5)][..]);
+
+        keylist.sort();
+        assert!(keylist == [("a", 5), ("b", 1), ("b", 2)][..]);
+        assert_eq!(keylist.get_sorted(&"b"), Some(&1));
+        assert_eq!(keylist.get_sorted(&"c"), None);
+    }
+
+    #[test]
+    fn conversions() {
+        let pairs = vec![("b", 2), ("a", 5), ("b", 1)];
+
+        assert!(data() == pairs[..]);
+        assert_eq!(data().into_iter().collect::<Vec<_>>(), pairs);
+        assert_eq!(
+            data().into_swapped(),
+            ArrayKeylist::<_, _, 4>::from_slice(&[(2, "b"), (5, "a"), (1, "b")]).unwrap()
+        );
+        assert_eq!(format!("{:?}", data()), r#"[("b", 2), ("a", 5), ("b", 1)]"#);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vec_keylist() {
+        use crate::VecKeylist;
+        use std::convert::TryFrom;
+
+        let pairs = vec![("b", 2), ("a", 5), ("b", 1)];
+
+        assert_eq!(VecKeylist::from(data()), VecKeylist(pairs.clone()));
+        assert_eq!(data(), VecKeylist(pairs.clone()));
+        assert_eq!(
+            ArrayKeylist::<_, _, 4>::try_from(VecKeylist(pairs.clone())),
+            Ok(data())
+        );
+        assert_eq!(
+            ArrayKeylist::<_, _, 2>::try_from(VecKeylist(pairs.clone())),
+            Err(VecKeylist(pairs))
+        );
+    }
+
+    #[test]
+    fn from_array() {
+        let keylist = ArrayKeylist::from([("b", 2), ("a", 5), ("b", 1)]);
+
+        assert!(keylist.is_full());
+        assert_eq!(keylist.get_all(&"b").collect::<Vec<_>>(), vec![&2, &1]);
+    }
+
+    #[test]
+    fn slice() {
+        let mut keylist = data();
+
+        assert!(keylist[1..] == [("a", 5), ("b", 1)][..]);
+        keylist[..2].sort_by_key();
+        assert!(keylist == [("a", 5), ("b", 2), ("b", 1)][..]);
+        assert_eq!(keylist[1..].get(&"b"), Some(&2));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use crate::ArrayKeylist;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+    #[test]
+    fn serde_de_map() {
+        let expected: ArrayKeylist<_, _, 2> =
+            ArrayKeylist::from_slice(&[("oke", 1), ("test", 15)]).unwrap();
+
+        assert_de_tokens(
+            &expected,
+            &[
+                Token::Map { len: Some(2) },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::MapEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn serde_de_too_long() {
+        assert_de_tokens_error::<ArrayKeylist<&str, i32, 1>>(
+            &[
+                Token::Map { len: Some(2) },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::MapEnd,
+            ],
+            "invalid length 2, expected Struct ArrayKeylist with at most 1 pairs",
+        );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let input: ArrayKeylist<_, _, 2> =
+            ArrayKeylist::from_slice(&[("oke", 1), ("test", 15)]).unwrap();
+
+        assert_tokens(
+            &input,
+            &[
+                Token::NewtypeStruct {
+                    name: "ArrayKeylist",
+                },
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
@@ -0,0 +1,405 @@
+//! A keylist with hashed lookups in both directions.
+//!
+//! Like [`HashKeylist`](crate::HashKeylist), but it also keeps an index from every value to
+//! its keys. Both indexes are updated on every mutation.
+//! ```
+//! use keylist::BiKeylist;
+//!
+//! let mut codes = BiKeylist::new();
+//! codes.push("NL", "Netherlands");
+//! codes.push("DE", "Germany");
+//! codes.push("NL", "Holland");
+//! codes.push("HOL", "Holland");
+//!
+//! assert_eq!(codes.get_by_key(&"NL"), Some(&"Netherlands"));
+//! assert_eq!(codes.get_by_value(&"Holland"), Some(&"NL"));
+//! assert_eq!(codes.get_all_by_value(&"Holland"), Some(&vec!["NL", "HOL"]));
+//!
+//! assert_eq!(codes.remove(2), ("NL", "Holland"));
+//! assert_eq!(codes.get_all_by_value(&"Holland"), Some(&vec!["HOL"]));
+//! ```
+
+use crate::hash_keylist::iter::{Iter, RowIter};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FromIterator;
+
+#[derive(Clone)]
+pub struct BiKeylist<K, V, S = RandomState>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher,
+{
+    by_key: HashMap<K, Vec<V>, S>,
+    by_value: HashMap<V, Vec<K>, S>,
+    keys: Vec<K>,
+}
+
+/// Removes the value at `pos` of the row, and the row itself when it becomes empty.
+fn take<A, B, S>(map: &mut HashMap<A, Vec<B>, S>, key: &A, pos: usize) -> B
+where
+    A: Hash + Eq,
+    S: BuildHasher,
+{
+    let row = map.get_mut(key).expect("indexes are out of sync");
+    let value = row.remove(pos);
+    if row.is_empty() {
+        map.remove(key);
+    }
+    value
+}
+
+impl<K, V> BiKeylist<K, V, RandomState>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+{
+    pub fn new() -> Self {
+        BiKeylist {
+            by_key: HashMap::new(),
+            by_value: HashMap::new(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl<K, V> Default for BiKeylist<K, V, RandomState>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> BiKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher + Clone,
+{
+    pub fn with_hasher(hash_builder: S) -> Self {
+        BiKeylist {
+            by_key: HashMap::with_hasher(hash_builder.clone()),
+            by_value: HashMap::with_hasher(hash_builder),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl<K, V, S> BiKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Iterates the pairs in insertion order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let map = HashMap::from_iter(
+            self.by_key
+                .iter()
+                .map(|(k, vs)| (k, RowIter { values: vs.iter() })),
+        );
+        Iter {
+            keys: self.keys.iter(),
+            map,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.keys.iter()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.by_key.contains_key(key)
+    }
+
+    pub fn contains_value(&self, value: &V) -> bool {
+        self.by_value.contains_key(value)
+    }
+
+    /// first value of the key
+    pub fn get_by_key(&self, key: &K) -> Option<&V> {
+        self.by_key.get(key)?.first()
+    }
+
+    /// first key with the value
+    pub fn get_by_value(&self, value: &V) -> Option<&K> {
+        self.by_value.get(value)?.first()
+    }
+
+    /// all values of the key, in order
+    pub fn get_all_by_key(&self, key: &K) -> Option<&Vec<V>> {
+        self.by_key.get(key)
+    }
+
+    /// all keys with the value, in order
+    pub fn get_all_by_value(&self, value: &V) -> Option<&Vec<K>> {
+        self.by_value.get(value)
+    }
+
+    /// How many times the key and the value of the pair at `index` occur before it.
+    fn occurrences(&self, index: usize, key: &K, value: &V) -> (usize, usize) {
+        self.iter()
+            .take(index)
+            .fold((0, 0), |(keys, values), (k, v)| {
+                (keys + (k == key) as usize, values + (v == value) as usize)
+            })
+    }
+
+    /// Removes the pair at the index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> (K, V) {
+        let (key_pos, value_pos) = {
+            let (key, value) = self.iter().nth(index).expect("index out of bounds");
+            self.occurrences(index, key, value)
+        };
+        let key = self.keys.remove(index);
+        let value = take(&mut self.by_key, &key, key_pos);
+        take(&mut self.by_value, &value, value_pos);
+        (key, value)
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.len() - 1))
+        }
+    }
+}
+
+impl<K, V, S> BiKeylist<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    pub fn push(&mut self, key: K, value: V) {
+        self.keys.push(key.clone());
+        self.by_key
+            .entry(key.clone())
+            .or_default()
+            .push(value.clone());
+        self.by_value.entry(value).or_default().push(key);
+    }
+
+    /// Inserts the pair at the index, shifting the pairs after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, key: K, value: V) {
+        assert!(index <= self.len(), "index out of bounds");
+        let (key_pos, value_pos) = self.occurrences(index, &key, &value);
+        self.keys.insert(index, key.clone());
+        self.by_key
+            .entry(key.clone())
+            .or_default()
+            .insert(key_pos, value.clone());
+        self.by_value
+            .entry(value)
+            .or_default()
+            .insert(value_pos, key);
+    }
+}
+
+impl<K, V, S> PartialEq for BiKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V, S> Eq for BiKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher,
+{
+}
+
+/// Formats as a list of pairs in insertion order: `[("a", 1), ("b", 2)]`.
+impl<K, V, S> fmt::Debug for BiKeylist<K, V, S>
+where
+    K: fmt::Debug + Hash + Eq,
+    V: fmt::Debug + Hash + Eq,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for BiKeylist<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.push(k, v);
+        }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for BiKeylist<K, V, RandomState>
+where
+    K: Hash + Eq + Clone,
+    V: Hash + Eq + Clone,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut keylist = BiKeylist::new();
+        keylist.extend(iter);
+        keylist
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BiKeylist;
+
+    fn data() -> BiKeylist<&'static str, u32> {
+        vec![("a", 1), ("b", 2), ("a", 2), ("c", 1), ("a", 1)]
+            .into_iter()
+            .collect()
+    }
+
+    /// Checks the value index against the pairs.
+    fn check(keylist: &BiKeylist<&'static str, u32>) {
+        let pairs: Vec<_> = keylist.iter().collect();
+        assert_eq!(pairs.len(), keylist.len());
+        for (_, v) in &pairs {
+            let expected: Vec<_> = pairs
+                .iter()
+                .filter(|(_, x)| x == v)
+                .map(|(k, _)| **k)
+                .collect();
+            assert_eq!(keylist.get_all_by_value(v), Some(&expected));
+        }
+        let total: usize = keylist.by_value.values().map(Vec::len).sum();
+        assert_eq!(total, keylist.len());
+    }
+
+    #[test]
+    fn lookups() {
+        let keylist = data();
+
+        assert_eq!(keylist.get_by_key(&"a"), Some(&1));
+        assert_eq!(keylist.get_by_value(&2), Some(&"b"));
+        assert_eq!(keylist.get_all_by_key(&"a"), Some(&vec![1, 2, 1]));
+        assert_eq!(keylist.get_all_by_value(&1), Some(&vec!["a", "c", "a"]));
+        assert_eq!(keylist.get_by_key(&"z"), None);
+        assert_eq!(keylist.get_all_by_value(&9), None);
+        assert!(keylist.contains_key(&"c"));
+        assert!(!keylist.contains_value(&3));
+        check(&keylist);
+    }
+
+    #[test]
+    fn iteration_order() {
+        let keylist = data();
+
+        let pairs: Vec<_> = keylist.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(
+            pairs,
+            vec![("a", 1), ("b", 2), ("a", 2), ("c", 1), ("a", 1)]
+        );
+        assert_eq!(
+            keylist.keys().collect::<Vec<_>>(),
+            vec![&"a", &"b", &"a", &"c", &"a"]
+        );
+        assert_eq!(
+            keylist.values().collect::<Vec<_>>(),
+            vec![&1, &2, &2, &1, &1]
+        );
+        assert_eq!(
+            format!("{:?}", keylist),
+            r#"[("a", 1), ("b", 2), ("a", 2), ("c", 1), ("a", 1)]"#
+        );
+    }
+
+    #[test]
+    fn remove() {
+        let mut keylist = data();
+
+        assert_eq!(keylist.remove(2), ("a", 2));
+        check(&keylist);
+        assert_eq!(keylist.get_all_by_key(&"a"), Some(&vec![1, 1]));
+        assert_eq!(keylist.get_all_by_value(&2), Some(&vec!["b"]));
+
+        assert_eq!(keylist.remove(0), ("a", 1));
+        check(&keylist);
+        assert_eq!(keylist.get_all_by_value(&1), Some(&vec!["c", "a"]));
+
+        assert_eq!(keylist.pop(), Some(("a", 1)));
+        assert_eq!(keylist.pop(), Some(("c", 1)));
+        check(&keylist);
+        assert_eq!(keylist.get_by_key(&"a"), None);
+        assert_eq!(keylist.get_by_value(&1), None);
+
+        assert_eq!(keylist.pop(), Some(("b", 2)));
+        assert_eq!(keylist.pop(), None);
+        assert_eq!(keylist, BiKeylist::new());
+    }
+
+    #[test]
+    fn insert() {
+        let mut keylist = data();
+
+        keylist.insert(1, "c", 2);
+        keylist.insert(0, "a", 3);
+        keylist.insert(keylist.len(), "a", 2);
+        check(&keylist);
+
+        let pairs: Vec<_> = keylist.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("a", 3),
+                ("a", 1),
+                ("c", 2),
+                ("b", 2),
+                ("a", 2),
+                ("c", 1),
+                ("a", 1),
+                ("a", 2)
+            ]
+        );
+        assert_eq!(
+            keylist.get_all_by_value(&2),
+            Some(&vec!["c", "b", "a", "a"])
+        );
+        assert_eq!(keylist.get_all_by_key(&"c"), Some(&vec![2, 1]));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn remove_out_of_bounds() {
+        data().remove(5);
+    }
+}
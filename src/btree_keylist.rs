@@ -0,0 +1,583 @@
+//! A keylist backed by a `BTreeMap`, for keys that are `Ord` but not `Hash`.
+//!
+//! `iter` follows the insertion order like the other keylists, `iter_sorted` and `range` follow the key order.
+//! ```
+//! use keylist::BTreeKeylist;
+//!
+//! let keylist = BTreeKeylist::from(vec![("b", 1), ("c", 2), ("a", 3), ("b", 4)]);
+//!
+//! assert_eq!(keylist.get(&"b"), Some(&1));
+//! assert_eq!(
+//!     keylist.iter().collect::<Vec<_>>(),
+//!     vec![(&"b", &1), (&"c", &2), (&"a", &3), (&"b", &4)]
+//! );
+//! assert_eq!(
+//!     keylist.iter_sorted().collect::<Vec<_>>(),
+//!     vec![(&"a", &3), (&"b", &1), (&"b", &4), (&"c", &2)]
+//! );
+//! assert_eq!(
+//!     keylist.range("b".."c").collect::<Vec<_>>(),
+//!     vec![(&"b", &1), (&"b", &4)]
+//! );
+//! ```
+
+use crate::VecKeylist;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::RangeBounds;
+
+pub mod iter;
+use iter::*;
+
+pub struct BTreeKeylist<K: Ord, V> {
+    pub(crate) data: BTreeMap<K, Vec<V>>,
+    pub(crate) keys: Vec<K>,
+}
+
+/// Formats as a list of pairs in insertion order: `[("a", 1), ("b", 2)]`.
+impl<K: Ord + fmt::Debug, V: fmt::Debug> fmt::Debug for BTreeKeylist<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Compares the ordered pairs, same as the values of each key with the same key order.
+impl<K: Ord, V: PartialEq> PartialEq for BTreeKeylist<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys && self.data == other.data
+    }
+}
+
+impl<K: Ord, V: Eq> Eq for BTreeKeylist<K, V> {}
+
+impl<K: Ord, V: PartialOrd> PartialOrd for BTreeKeylist<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K: Ord, V: Ord> Ord for BTreeKeylist<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+/// Hashes the same as a `VecKeylist` or `Vec` with the same pairs.
+impl<K: Ord + Hash, V: Hash> Hash for BTreeKeylist<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for pair in self.iter() {
+            pair.hash(state);
+        }
+    }
+}
+
+impl<K: Ord, V> Default for BTreeKeylist<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V: PartialEq> PartialEq<VecKeylist<K, V>> for BTreeKeylist<K, V> {
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl<K: Ord, V: PartialEq> PartialEq<BTreeKeylist<K, V>> for VecKeylist<K, V> {
+    fn eq(&self, other: &BTreeKeylist<K, V>) -> bool {
+        other == self
+    }
+}
+
+impl<K: Ord, V> BTreeKeylist<K, V> {
+    pub fn new() -> Self {
+        BTreeKeylist {
+            data: BTreeMap::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        BTreeKeylist {
+            data: BTreeMap::new(),
+            keys: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Iterates in insertion order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let map = BTreeMap::from_iter(
+            self.data
+                .iter()
+                .map(|(k, vs)| (k, RowIter { values: vs.iter() })),
+        );
+        Iter {
+            keys: self.keys.iter(),
+            map,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let map = BTreeMap::from_iter(self.data.iter_mut().map(|(k, vs)| {
+            (
+                k,
+                RowIterMut {
+                    values: vs.iter_mut(),
+                },
+            )
+        }));
+        IterMut {
+            keys: self.keys.iter(),
+            map,
+        }
+    }
+
+    /// Iterates in key order, the values of a key stay in insertion order.
+    pub fn iter_sorted(&self) -> SortedIter<'_, K, V> {
+        self.range(..)
+    }
+
+    /// The pairs with a key in the range, in key order.
+    ///
+    /// # Panics
+    ///
+    /// Panics like `BTreeMap::range` when the start is greater than the end.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> SortedIter<'_, K, V> {
+        SortedIter {
+            rows: self.data.range(range),
+            row: None,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.keys.iter()
+    }
+
+    /// The distinct keys, in key order.
+    pub fn keys_sorted(&self) -> impl Iterator<Item = &K> {
+        self.data.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.iter_mut().map(|(_, v)| v)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let key = self.keys.pop()?;
+        let value = self.pop_and_clean(&key)?;
+        Some((key, value))
+    }
+
+    pub fn remove(&mut self, index: usize) -> (K, V) {
+        let key = self.keys.remove(index);
+        let value = self.remove_and_clean(&key, index).unwrap();
+        (key, value)
+    }
+
+    /// Removes all the values of the key, returns `None` if the key is not in the list.
+    pub fn remove_all(&mut self, key: &K) -> Option<Vec<V>> {
+        let values = self.data.remove(key)?;
+        self.keys.retain(|k| k != key);
+        Some(values)
+    }
+
+    fn pop_and_clean(&mut self, key: &K) -> Option<V> {
+        let list = self.get_all_mut(key)?;
+        let value = list.pop();
+        if list.is_empty() {
+            self.data.remove(key);
+        }
+        value
+    }
+
+    fn remove_and_clean(&mut self, key: &K, index: usize) -> Option<V> {
+        let pos = self.index_to_position(key, index);
+        let list = self.get_all_mut(key)?;
+        let value = list.remove(pos);
+        if list.is_empty() {
+            self.data.remove(key);
+        }
+        Some(value)
+    }
+
+    fn index_to_position(&self, key: &K, index: usize) -> usize {
+        self.keys.iter().take(index).filter(|k| *k == key).count()
+    }
+
+    pub fn get_key_value(&self, key: &K) -> Option<(&K, &V)> {
+        let (k, vs) = self.data.get_key_value(key)?;
+        Some((k, vs.first()?))
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.data.get(key)?.first()
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.data.get_mut(key)?.first_mut()
+    }
+
+    pub fn get_all(&self, key: &K) -> Option<&Vec<V>> {
+        self.data.get(key)
+    }
+
+    /// Same caveat as `HashKeylist::get_all_mut`, pushing to the list does not add the key, use `push` for that.
+    pub fn get_all_mut(&mut self, key: &K) -> Option<&mut Vec<V>> {
+        self.data.get_mut(key)
+    }
+
+    pub fn get_all_key_value<'a>(&'a self, key: &'a K) -> IterKeyValue<'a, K, V> {
+        match self.data.get_key_value(key) {
+            Some((x, y)) => IterKeyValue {
+                key: x,
+                values: y.iter(),
+            },
+            None => IterKeyValue {
+                key,
+                values: [].iter(),
+            },
+        }
+    }
+
+    /// The first pair with the smallest key.
+    pub fn first_sorted(&self) -> Option<(&K, &V)> {
+        let (k, vs) = self.data.iter().next()?;
+        Some((k, vs.first()?))
+    }
+
+    /// The last pair with the largest key.
+    pub fn last_sorted(&self) -> Option<(&K, &V)> {
+        let (k, vs) = self.data.iter().next_back()?;
+        Some((k, vs.last()?))
+    }
+
+    /// Sorts the insertion order by key, the values of a key keep their order.
+    pub fn sort_by_key(&mut self) {
+        self.keys.sort_unstable()
+    }
+}
+
+impl<K: Ord, V: Ord> BTreeKeylist<K, V> {
+    pub fn sort(&mut self) {
+        self.sort_by_key();
+        for item in self.data.values_mut() {
+            item.sort();
+        }
+    }
+}
+
+impl<K: Ord + Clone, V> BTreeKeylist<K, V> {
+    pub fn insert(&mut self, index: usize, key: K, value: V) {
+        let pos = self.index_to_position(&key, index);
+        let entry = self.data.entry(key.clone()).or_default();
+        entry.insert(pos, value);
+        self.keys.insert(index, key);
+    }
+
+    pub fn push(&mut self, k: K, v: V) {
+        let entry = self.data.entry(k.clone()).or_default();
+        entry.push(v);
+        self.keys.push(k)
+    }
+}
+
+impl<K: Ord + Clone, V> From<Vec<(K, V)>> for BTreeKeylist<K, V> {
+    fn from(input: Vec<(K, V)>) -> Self {
+        input.into_iter().collect()
+    }
+}
+
+impl<K: Ord + Clone, V> From<VecKeylist<K, V>> for BTreeKeylist<K, V> {
+    fn from(input: VecKeylist<K, V>) -> Self {
+        input.0.into_iter().collect()
+    }
+}
+
+impl<K: Ord + Clone, V, const N: usize> From<[(K, V); N]> for BTreeKeylist<K, V> {
+    fn from(input: [(K, V); N]) -> Self {
+        IntoIterator::into_iter(input).collect()
+    }
+}
+
+impl<K: Ord, V> From<BTreeKeylist<K, V>> for VecKeylist<K, V> {
+    fn from(input: BTreeKeylist<K, V>) -> Self {
+        VecKeylist(input.into_iter().collect())
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(K, V)> for BTreeKeylist<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let iter = iter.into_iter();
+        let (size, _) = iter.size_hint();
+        let mut keylist = BTreeKeylist::with_capacity(size);
+        keylist.extend(iter);
+        keylist
+    }
+}
+
+impl<K: Ord + Clone, V> Extend<(K, V)> for BTreeKeylist<K, V> {
+    #[inline]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (k, v) in iter {
+            self.push(k, v);
+        }
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Clone for BTreeKeylist<K, V> {
+    fn clone(&self) -> Self {
+        BTreeKeylist {
+            data: self.data.clone(),
+            keys: self.keys.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use crate::BTreeKeylist;
+    use alloc::vec::Vec;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<K, V> Serialize for BTreeKeylist<K, V>
+    where
+        K: Serialize + Ord,
+        V: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_newtype_struct("BTreeKeylist", &self.iter().collect::<Vec<_>>())
+        }
+    }
+
+    /// Accepts the same input as `VecKeylist`: a list of pairs or a map.
+    impl<'de, K, V> Deserialize<'de> for BTreeKeylist<K, V>
+    where
+        K: Deserialize<'de> + Ord + Clone,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let keylist: crate::VecKeylist<K, V> = Deserialize::deserialize(deserializer)?;
+            Ok(BTreeKeylist::from(keylist))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BTreeKeylist, VecKeylist};
+
+    fn data() -> BTreeKeylist<&'static str, u32> {
+        BTreeKeylist::from(vec![("oke", 1), ("test", 19), ("oke", 2), ("abc", 7)])
+    }
+
+    #[test]
+    fn iter() {
+        let keylist = data();
+
+        let mut iter = keylist.iter();
+        assert_eq!(Some((&"oke", &1)), iter.next());
+        assert_eq!(Some((&"test", &19)), iter.next());
+        assert_eq!(Some((&"oke", &2)), iter.next());
+        assert_eq!(Some((&"abc", &7)), iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn iter_sorted() {
+        let keylist = data();
+
+        assert_eq!(
+            keylist.iter_sorted().collect::<Vec<_>>(),
+            vec![(&"abc", &7), (&"oke", &1), (&"oke", &2), (&"test", &19)]
+        );
+        assert_eq!(
+            keylist.keys_sorted().collect::<Vec<_>>(),
+            vec![&"abc", &"oke", &"test"]
+        );
+        assert_eq!(keylist.first_sorted(), Some((&"abc", &7)));
+        assert_eq!(keylist.last_sorted(), Some((&"test", &19)));
+        assert_eq!(BTreeKeylist::<u8, u8>::new().iter_sorted().next(), None);
+    }
+
+    #[test]
+    fn range() {
+        let keylist = data();
+
+        assert_eq!(
+            keylist.range("b".."p").collect::<Vec<_>>(),
+            vec![(&"oke", &1), (&"oke", &2)]
+        );
+        assert_eq!(
+            keylist.range(.."oke").collect::<Vec<_>>(),
+            vec![(&"abc", &7)]
+        );
+        assert_eq!(
+            keylist
+                .range("oke"..="test")
+                .map(|(_, v)| *v)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 19]
+        );
+        assert_eq!(keylist.range("x"..).next(), None);
+    }
+
+    #[test]
+    fn get() {
+        let mut keylist = data();
+
+        assert_eq!(keylist.get(&"oke"), Some(&1));
+        assert_eq!(keylist.get_key_value(&"test"), Some((&"test", &19)));
+        assert_eq!(keylist.get_all(&"oke"), Some(&vec![1, 2]));
+        assert_eq!(keylist.get(&"other"), None);
+        assert_eq!(
+            keylist.get_all_key_value(&"oke").collect::<Vec<_>>(),
+            vec![(&"oke", &1), (&"oke", &2)]
+        );
+
+        *keylist.get_mut(&"oke").unwrap() = 5;
+        for v in keylist.values_mut() {
+            *v += 1;
+        }
+        assert_eq!(keylist.values().collect::<Vec<_>>(), vec![&6, &20, &3, &8]);
+    }
+
+    #[test]
+    fn push_insert_remove() {
+        let mut keylist = data();
+
+        keylist.insert(1, "oke", 0);
+        assert_eq!(keylist.get_all(&"oke"), Some(&vec![1, 0, 2]));
+
+        assert_eq!(keylist.remove(2), ("test", 19));
+        assert_eq!(keylist.remove(1), ("oke", 0));
+        assert_eq!(keylist.pop(), Some(("abc", 7)));
+        assert_eq!(keylist.get(&"abc"), None);
+
+        keylist.push("abc", 3);
+        assert_eq!(keylist.remove_all(&"oke"), Some(vec![1, 2]));
+        assert_eq!(keylist.remove_all(&"oke"), None);
+        assert_eq!(keylist, VecKeylist(vec![("abc", 3)]));
+        assert_eq!(keylist.len(), 1);
+        assert!(!keylist.is_empty());
+    }
+
+    #[test]
+    fn sort() {
+        let mut keylist = BTreeKeylist::from(vec![("b", 2), ("a", 1), ("b", 1)]);
+
+        keylist.sort_by_key();
+        assert_eq!(keylist, VecKeylist(vec![("a", 1), ("b", 2), ("b", 1)]));
+
+        keylist.sort();
+        assert_eq!(keylist, VecKeylist(vec![("a", 1), ("b", 1), ("b", 2)]));
+    }
+
+    #[test]
+    fn keys_without_hash() {
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct Version(u8, u8);
+
+        let keylist: BTreeKeylist<_, _> = vec![
+            (Version(1, 2), "b"),
+            (Version(0, 9), "a"),
+            (Version(1, 2), "c"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(keylist.get_all(&Version(1, 2)), Some(&vec!["b", "c"]));
+        assert_eq!(keylist.range(Version(1, 0)..).count(), 2);
+    }
+
+    #[test]
+    fn conversions() {
+        let pairs = vec![("oke", 1), ("test", 19), ("oke", 2), ("abc", 7)];
+
+        assert_eq!(data(), VecKeylist(pairs.clone()));
+        assert_eq!(VecKeylist::from(data()), VecKeylist(pairs.clone()));
+        assert_eq!(data().into_iter().collect::<Vec<_>>(), pairs);
+        assert_eq!(data().clone(), data());
+        assert_eq!(
+            format!("{:?}", data()),
+            r#"[("oke", 1), ("test", 19), ("oke", 2), ("abc", 7)]"#
+        );
+    }
+
+    #[test]
+    fn from_array() {
+        let keylist = BTreeKeylist::from([("oke", 1), ("test", 19), ("oke", 2), ("abc", 7)]);
+
+        assert_eq!(keylist, data());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use crate::BTreeKeylist;
+    use serde_test::{assert_de_tokens, assert_ser_tokens, Token};
+
+    #[test]
+    fn serde_de_map() {
+        let expected = BTreeKeylist::from(vec![("oke", 1), ("test", 15)]);
+
+        assert_de_tokens(
+            &expected,
+            &[
+                Token::Map { len: Some(2) },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::MapEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn serde_ser() {
+        let input = BTreeKeylist::from(vec![("test", 15), ("oke", 1)]);
+
+        assert_ser_tokens(
+            &input,
+            &[
+                Token::NewtypeStruct {
+                    name: "BTreeKeylist",
+                },
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::Str("test"),
+                Token::I32(15),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::Str("oke"),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
@@ -0,0 +1,86 @@
+pub use crate::iter::{IterKeyValue, RowIter, RowIterMut};
+
+use crate::BTreeKeylist;
+use alloc::collections::btree_map;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+impl<K: Ord, V> IntoIterator for BTreeKeylist<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            keys: self.keys.into_iter(),
+            map: self
+                .data
+                .into_iter()
+                .map(|(k, vs)| (k, vs.into_iter()))
+                .collect(),
+        }
+    }
+}
+
+pub struct IntoIter<K, V> {
+    pub(crate) keys: alloc::vec::IntoIter<K>,
+    pub(crate) map: BTreeMap<K, alloc::vec::IntoIter<V>>,
+}
+
+impl<K: Ord, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        let value = self.map.get_mut(&key)?.next()?;
+        Some((key, value))
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    pub(crate) keys: core::slice::Iter<'a, K>,
+    pub(crate) map: BTreeMap<&'a K, RowIter<'a, V>>,
+}
+
+impl<'a, K: Ord, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        let value = self.map.get_mut(key)?.next()?;
+        Some((key, value))
+    }
+}
+
+pub struct IterMut<'a, K, V> {
+    pub(crate) keys: core::slice::Iter<'a, K>,
+    pub(crate) map: BTreeMap<&'a K, RowIterMut<'a, V>>,
+}
+
+impl<'a, K: Ord, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        let value = self.map.get_mut(key)?.next()?;
+        Some((key, value))
+    }
+}
+
+/// Iterates in key order, the values of a key in insertion order.
+pub struct SortedIter<'a, K, V> {
+    pub(crate) rows: btree_map::Range<'a, K, Vec<V>>,
+    pub(crate) row: Option<IterKeyValue<'a, K, V>>,
+}
+
+impl<'a, K, V> Iterator for SortedIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pair) = self.row.as_mut().and_then(Iterator::next) {
+                return Some(pair);
+            }
+            let (key, values) = self.rows.next()?;
+            self.row = Some(IterKeyValue {
+                key,
+                values: values.iter(),
+            });
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! Conversions between the keylist types and the std maps.
+//!
+//! Converting to a map needs a policy for duplicate keys: keep the first value, keep the last
+//! value, or keep all values in a multimap. Like the other `HashKeylist` conversions with `std`,
+//! the ones that build a `HashKeylist` use `RandomState`.
+//! ```
+//! use keylist::{HashKeylist, VecKeylist};
+//!
+//! let keylist = VecKeylist(vec![("a", 1), ("b", 2), ("a", 3)]);
+//!
+//! assert_eq!(keylist.clone().into_hash_map_first()["a"], 1);
+//! assert_eq!(keylist.clone().into_hash_map_last()["a"], 3);
+//! assert_eq!(keylist.clone().into_btree_multimap()["a"], vec![1, 3]);
+//!
+//! let hash_keylist = HashKeylist::from(keylist.clone());
+//! assert_eq!(VecKeylist::from(hash_keylist), keylist);
+//!
+//! let sorted = VecKeylist::from_multimap(keylist.into_btree_multimap());
+//! assert_eq!(sorted, VecKeylist(vec![("a", 1), ("a", 3), ("b", 2)]));
+//! ```
+
+use crate::{HashKeylist, VecKeylist};
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+impl<K, V> From<VecKeylist<K, V>> for HashKeylist<K, V, RandomState>
+where
+    K: Hash + Eq + Clone,
+    V: Eq,
+{
+    fn from(input: VecKeylist<K, V>) -> Self {
+        HashKeylist::from(input.0)
+    }
+}
+
+impl<K, V, S> From<HashKeylist<K, V, S>> for VecKeylist<K, V>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn from(input: HashKeylist<K, V, S>) -> Self {
+        let mut pairs = Vec::with_capacity(input.len());
+        pairs.extend(input);
+        VecKeylist(pairs)
+    }
+}
+
+impl<K: Hash + Eq, V> VecKeylist<K, V> {
+    /// Keeps the first value of every key.
+    pub fn into_hash_map_first(self) -> HashMap<K, V> {
+        let mut map = HashMap::with_capacity(self.len());
+        for (k, v) in self.0 {
+            map.entry(k).or_insert(v);
+        }
+        map
+    }
+
+    /// Keeps the last value of every key, like `collect` does.
+    pub fn into_hash_map_last(self) -> HashMap<K, V> {
+        self.0.into_iter().collect()
+    }
+
+    /// All values of every key, in order.
+    pub fn into_multimap(self) -> HashMap<K, Vec<V>> {
+        let mut map: HashMap<K, Vec<V>> = HashMap::with_capacity(self.len());
+        for (k, v) in self.0 {
+            map.entry(k).or_default().push(v);
+        }
+        map
+    }
+}
+
+impl<K: Ord, V> VecKeylist<K, V> {
+    /// Keeps the first value of every key.
+    pub fn into_btree_map_first(self) -> BTreeMap<K, V> {
+        let mut map = BTreeMap::new();
+        for (k, v) in self.0 {
+            map.entry(k).or_insert(v);
+        }
+        map
+    }
+
+    /// Keeps the last value of every key, like `collect` does.
+    pub fn into_btree_map_last(self) -> BTreeMap<K, V> {
+        self.0.into_iter().collect()
+    }
+
+    /// All values of every key, in order.
+    pub fn into_btree_multimap(self) -> BTreeMap<K, Vec<V>> {
+        let mut map: BTreeMap<K, Vec<V>> = BTreeMap::new();
+        for (k, v) in self.0 {
+            map.entry(k).or_default().push(v);
+        }
+        map
+    }
+}
+
+impl<K: Clone, V> VecKeylist<K, V> {
+    /// The values of each key end up next to each other, the keys in the order of the input.
+    pub fn from_multimap<I>(input: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Vec<V>)>,
+    {
+        let mut pairs = Vec::new();
+        for (k, vs) in input {
+            pairs.extend(vs.into_iter().map(|v| (k.clone(), v)));
+        }
+        VecKeylist(pairs)
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher + Clone,
+{
+    /// Keeps the first value of every key.
+    pub fn into_hash_map_first(self) -> HashMap<K, V, S> {
+        let mut map =
+            HashMap::with_capacity_and_hasher(self.data.len(), self.data.hasher().clone());
+        map.extend(
+            self.data
+                .into_iter()
+                .filter_map(|(k, vs)| Some((k, vs.into_iter().next()?))),
+        );
+        map
+    }
+
+    /// Keeps the last value of every key.
+    pub fn into_hash_map_last(self) -> HashMap<K, V, S> {
+        let mut map =
+            HashMap::with_capacity_and_hasher(self.data.len(), self.data.hasher().clone());
+        map.extend(
+            self.data
+                .into_iter()
+                .filter_map(|(k, mut vs)| Some((k, vs.pop()?))),
+        );
+        map
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    /// All values of every key, in order. This is the internal map, so it does not allocate.
+    pub fn into_multimap(self) -> HashMap<K, Vec<V>, S> {
+        self.data
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Ord,
+    V: Eq,
+    S: BuildHasher,
+{
+    /// Keeps the first value of every key.
+    pub fn into_btree_map_first(self) -> BTreeMap<K, V> {
+        self.data
+            .into_iter()
+            .filter_map(|(k, vs)| Some((k, vs.into_iter().next()?)))
+            .collect()
+    }
+
+    /// Keeps the last value of every key.
+    pub fn into_btree_map_last(self) -> BTreeMap<K, V> {
+        self.data
+            .into_iter()
+            .filter_map(|(k, mut vs)| Some((k, vs.pop()?)))
+            .collect()
+    }
+
+    /// All values of every key, in order.
+    pub fn into_btree_multimap(self) -> BTreeMap<K, Vec<V>> {
+        self.data.into_iter().collect()
+    }
+}
+
+impl<K, V> HashKeylist<K, V, RandomState>
+where
+    K: Hash + Eq + Clone,
+    V: Eq,
+{
+    /// The values of each key end up next to each other, the keys in the order of the input.
+    /// The value vectors are reused.
+    pub fn from_multimap<I>(input: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Vec<V>)>,
+    {
+        let mut keylist = HashKeylist::new();
+        for (k, vs) in input {
+            if vs.is_empty() {
+                continue;
+            }
+            for _ in 0..vs.len() {
+                keylist.keys.push(k.clone());
+            }
+            match keylist.data.get_mut(&k) {
+                Some(existing) => existing.extend(vs),
+                None => {
+                    keylist.data.insert(k, vs);
+                }
+            }
+        }
+        keylist
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HashKeylist, VecKeylist};
+    use std::collections::hash_map::RandomState;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn keylist() -> VecKeylist<&'static str, i32> {
+        VecKeylist(vec![("b", 1), ("a", 2), ("b", 3), ("c", 4), ("b", 5)])
+    }
+
+    fn hash_keylist() -> HashKeylist<&'static str, i32, RandomState> {
+        keylist().into()
+    }
+
+    #[test]
+    fn between_keylists() {
+        let hash_keylist = HashKeylist::from(keylist());
+
+        assert_eq!(hash_keylist, keylist());
+        assert_eq!(VecKeylist::from(hash_keylist), keylist());
+        assert_eq!(
+            VecKeylist::from(HashKeylist::<u8, u8, RandomState>::new()),
+            VecKeylist::new()
+        );
+    }
+
+    #[test]
+    fn hash_maps() {
+        let first: HashMap<_, _> = vec![("b", 1), ("a", 2), ("c", 4)].into_iter().collect();
+        let last: HashMap<_, _> = vec![("b", 5), ("a", 2), ("c", 4)].into_iter().collect();
+        let multi: HashMap<_, _> = vec![("b", vec![1, 3, 5]), ("a", vec![2]), ("c", vec![4])]
+            .into_iter()
+            .collect();
+
+        assert_eq!(keylist().into_hash_map_first(), first);
+        assert_eq!(keylist().into_hash_map_last(), last);
+        assert_eq!(keylist().into_multimap(), multi);
+        assert_eq!(hash_keylist().into_hash_map_first(), first);
+        assert_eq!(hash_keylist().into_hash_map_last(), last);
+        assert_eq!(hash_keylist().into_multimap(), multi);
+    }
+
+    #[test]
+    fn btree_maps() {
+        let first: BTreeMap<_, _> = vec![("b", 1), ("a", 2), ("c", 4)].into_iter().collect();
+        let last: BTreeMap<_, _> = vec![("b", 5), ("a", 2), ("c", 4)].into_iter().collect();
+        let multi: BTreeMap<_, _> = vec![("b", vec![1, 3, 5]), ("a", vec![2]), ("c", vec![4])]
+            .into_iter()
+            .collect();
+
+        assert_eq!(keylist().into_btree_map_first(), first);
+        assert_eq!(keylist().into_btree_map_last(), last);
+        assert_eq!(keylist().into_btree_multimap(), multi);
+        assert_eq!(hash_keylist().into_btree_map_first(), first);
+        assert_eq!(hash_keylist().into_btree_map_last(), last);
+        assert_eq!(hash_keylist().into_btree_multimap(), multi);
+    }
+
+    #[test]
+    fn from_multimap() {
+        let input = vec![
+            ("b", vec![1, 3]),
+            ("a", vec![]),
+            ("c", vec![4]),
+            ("b", vec![5]),
+        ];
+        let expected = VecKeylist(vec![("b", 1), ("b", 3), ("c", 4), ("b", 5)]);
+
+        assert_eq!(VecKeylist::from_multimap(input.clone()), expected);
+
+        let keylist = HashKeylist::from_multimap(input);
+        assert_eq!(keylist, expected);
+        assert_eq!(keylist.get_all(&"b"), Some(&vec![1, 3, 5]));
+        assert_eq!(keylist.get_all(&"a"), None);
+    }
+
+    #[test]
+    fn multimap_round_trip() {
+        let multi = keylist().into_btree_multimap();
+
+        assert_eq!(
+            VecKeylist::from_multimap(multi.clone()),
+            VecKeylist(vec![("a", 2), ("b", 1), ("b", 3), ("b", 5), ("c", 4)])
+        );
+        assert_eq!(
+            VecKeylist::from_multimap(multi).into_btree_multimap(),
+            keylist().into_btree_multimap()
+        );
+    }
+}
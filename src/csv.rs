@@ -0,0 +1,510 @@
+//! Two-column CSV and TSV files, with [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180) quoting.
+//!
+//! Keys and values are parsed with `FromStr` and written with `Display`.
+//! ```
+//! use keylist::csv::Dialect;
+//! use keylist::VecKeylist;
+//!
+//! let input = "name,port\nweb,80\n\"db, primary\",5432\nweb,8080\n";
+//! let dialect = Dialect::csv().with_header("name", "port");
+//!
+//! let keylist: VecKeylist<String, u16> = VecKeylist::from_csv(input.as_bytes(), &dialect).unwrap();
+//! assert_eq!(keylist.get_all(&"web".to_string()), vec![&80, &8080]);
+//! assert_eq!(keylist.get(&"db, primary".to_string()), Some(&5432));
+//!
+//! let mut output = Vec::new();
+//! keylist.write_csv(&mut output, &dialect).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(output).unwrap(),
+//!     "name,port\r\nweb,80\r\n\"db, primary\",5432\r\nweb,8080\r\n"
+//! );
+//! ```
+
+use crate::{HashKeylist, VecKeylist};
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, BufRead, Write};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dialect {
+    pub delimiter: char,
+    /// the first row is a header: it is checked and skipped when reading and written when writing
+    pub header: Option<(String, String)>,
+}
+
+impl Dialect {
+    /// Comma separated, without header.
+    pub fn csv() -> Self {
+        Dialect {
+            delimiter: ',',
+            header: None,
+        }
+    }
+
+    /// Tab separated, without header.
+    pub fn tsv() -> Self {
+        Dialect {
+            delimiter: '\t',
+            header: None,
+        }
+    }
+
+    pub fn with_header(mut self, key: &str, value: &str) -> Self {
+        self.header = Some((key.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect::csv()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnterminatedQuote,
+    /// the first record is not the header of the dialect
+    Header,
+    /// a record without exactly two fields
+    FieldCount(usize),
+    /// the `FromStr` error message of the key
+    InvalidKey(String),
+    /// the `FromStr` error message of the value
+    InvalidValue(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnterminatedQuote => f.write_str("unterminated quote"),
+            ErrorKind::Header => f.write_str("expected the header"),
+            ErrorKind::FieldCount(n) => write!(f, "expected 2 fields, found {}", n),
+            ErrorKind::InvalidKey(e) => write!(f, "invalid key: {}", e),
+            ErrorKind::InvalidValue(e) => write!(f, "invalid value: {}", e),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// error with the 1-based line number where the record starts
+    Parse {
+        line: usize,
+        kind: ErrorKind,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => e.fmt(f),
+            Error::Parse { line, kind } => write!(f, "line {}: {}", line, kind),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Unquoted,
+    Quoted,
+    /// a quote inside a quoted field, either the closing one or the first half of `""`
+    AfterQuote,
+}
+
+/// Iterator over the parsed rows of a reader, see [`pairs`].
+#[derive(Debug)]
+pub struct Pairs<R, K, V> {
+    reader: R,
+    delimiter: char,
+    header: Option<(String, String)>,
+    line: usize,
+    marker: PhantomData<fn() -> (K, V)>,
+}
+
+/// Reads the rows one at a time, without collecting them.
+pub fn pairs<R, K, V>(reader: R, dialect: &Dialect) -> Pairs<R, K, V>
+where
+    R: BufRead,
+    K: FromStr,
+    V: FromStr,
+{
+    Pairs {
+        reader,
+        delimiter: dialect.delimiter,
+        header: dialect.header.clone(),
+        line: 0,
+        marker: PhantomData,
+    }
+}
+
+impl<R: BufRead, K, V> Pairs<R, K, V> {
+    /// Reads the next record and its line number, empty lines are skipped.
+    fn record(&mut self) -> Result<Option<(usize, Vec<String>)>, Error> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            if !line.trim_end_matches(['\r', '\n']).is_empty() {
+                break;
+            }
+        }
+
+        let start = self.line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut state = State::Start;
+        loop {
+            // the line terminator ends the record, unless it is inside a quoted field
+            let body = line.strip_suffix('\n').unwrap_or(&line);
+            let body = body.strip_suffix('\r').unwrap_or(body);
+            for c in body.chars() {
+                state = match (state, c) {
+                    (State::Quoted, '"') => State::AfterQuote,
+                    (State::Quoted, c) => {
+                        field.push(c);
+                        State::Quoted
+                    }
+                    (State::AfterQuote, '"') => {
+                        field.push('"');
+                        State::Quoted
+                    }
+                    (_, c) if c == self.delimiter => {
+                        fields.push(std::mem::take(&mut field));
+                        State::Start
+                    }
+                    (State::Start, '"') => State::Quoted,
+                    (_, c) => {
+                        field.push(c);
+                        State::Unquoted
+                    }
+                };
+            }
+
+            if state != State::Quoted {
+                break;
+            }
+            field.push_str(&line[body.len()..]);
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(Error::Parse {
+                    line: start,
+                    kind: ErrorKind::UnterminatedQuote,
+                });
+            }
+            self.line += 1;
+        }
+        fields.push(field);
+
+        Ok(Some((start, fields)))
+    }
+}
+
+impl<R, K, V> Iterator for Pairs<R, K, V>
+where
+    R: BufRead,
+    K: FromStr,
+    K::Err: Display,
+    V: FromStr,
+    V::Err: Display,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((key, value)) = self.header.take() {
+            match self.record() {
+                Ok(Some((_, fields))) if fields == [key, value] => (),
+                Ok(Some((line, _))) => {
+                    return Some(Err(Error::Parse {
+                        line,
+                        kind: ErrorKind::Header,
+                    }))
+                }
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        let (line, fields) = match self.record() {
+            Ok(record) => record?,
+            Err(e) => return Some(Err(e)),
+        };
+        let error = |kind| Some(Err(Error::Parse { line, kind }));
+        if fields.len() != 2 {
+            return error(ErrorKind::FieldCount(fields.len()));
+        }
+        let key = match fields[0].parse() {
+            Ok(key) => key,
+            Err(e) => return error(ErrorKind::InvalidKey(format!("{}", e))),
+        };
+        let value = match fields[1].parse() {
+            Ok(value) => value,
+            Err(e) => return error(ErrorKind::InvalidValue(format!("{}", e))),
+        };
+        Some(Ok((key, value)))
+    }
+}
+
+fn write_field<W: Write>(writer: &mut W, field: &str, delimiter: char) -> io::Result<()> {
+    if field.contains([delimiter, '"', '\r', '\n']) {
+        write!(writer, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        writer.write_all(field.as_bytes())
+    }
+}
+
+/// Writes the rows with `\r\n` line endings.
+fn write_rows<'a, W, K, V, I>(mut writer: W, dialect: &Dialect, rows: I) -> io::Result<()>
+where
+    W: Write,
+    K: Display + 'a,
+    V: Display + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    let delimiter = dialect.delimiter;
+    let mut separator = [0; 4];
+    let separator = delimiter.encode_utf8(&mut separator).as_bytes();
+
+    if let Some((k, v)) = &dialect.header {
+        write_field(&mut writer, k, delimiter)?;
+        writer.write_all(separator)?;
+        write_field(&mut writer, v, delimiter)?;
+        writer.write_all(b"\r\n")?;
+    }
+    for (k, v) in rows {
+        write_field(&mut writer, &k.to_string(), delimiter)?;
+        writer.write_all(separator)?;
+        write_field(&mut writer, &v.to_string(), delimiter)?;
+        writer.write_all(b"\r\n")?;
+    }
+    Ok(())
+}
+
+impl<K, V> VecKeylist<K, V>
+where
+    K: FromStr,
+    K::Err: Display,
+    V: FromStr,
+    V::Err: Display,
+{
+    pub fn from_csv<R: BufRead>(reader: R, dialect: &Dialect) -> Result<Self, Error> {
+        pairs(reader, dialect).collect()
+    }
+}
+
+impl<K: Display, V: Display> VecKeylist<K, V> {
+    pub fn write_csv<W: Write>(&self, writer: W, dialect: &Dialect) -> io::Result<()> {
+        write_rows(writer, dialect, self.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: FromStr + Hash + Eq + Clone,
+    K::Err: Display,
+    V: FromStr + Eq,
+    V::Err: Display,
+    S: BuildHasher + Default,
+{
+    pub fn from_csv<R: BufRead>(reader: R, dialect: &Dialect) -> Result<Self, Error> {
+        let mut keylist = HashKeylist::with_hasher(S::default());
+        for pair in pairs(reader, dialect) {
+            let (k, v) = pair?;
+            keylist.push(k, v);
+        }
+        Ok(keylist)
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Display + Hash + Eq,
+    V: Display + Eq,
+    S: BuildHasher,
+{
+    pub fn write_csv<W: Write>(&self, writer: W, dialect: &Dialect) -> io::Result<()> {
+        write_rows(writer, dialect, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pairs, Dialect, Error, ErrorKind};
+    use crate::{HashKeylist, VecKeylist};
+    use std::collections::hash_map::RandomState;
+
+    fn keylist(pairs: Vec<(&str, &str)>) -> VecKeylist<String, String> {
+        pairs
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn read(input: &str, dialect: &Dialect) -> Result<VecKeylist<String, String>, Error> {
+        VecKeylist::from_csv(input.as_bytes(), dialect)
+    }
+
+    fn write(keylist: &VecKeylist<String, String>, dialect: &Dialect) -> String {
+        let mut output = Vec::new();
+        keylist.write_csv(&mut output, dialect).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn error(input: &str) -> (usize, ErrorKind) {
+        match read(input, &Dialect::csv()) {
+            Err(Error::Parse { line, kind }) => (line, kind),
+            x => panic!("expected parse error, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn quoting() {
+        let input =
+            "a,1\r\n\"b,c\",\"say \"\"hi\"\"\"\n\n\"multi\nline\",\"\"\nd,\"x\" y\ne,\"\r\n\"";
+        let expected = keylist(vec![
+            ("a", "1"),
+            ("b,c", "say \"hi\""),
+            ("multi\nline", ""),
+            ("d", "x y"),
+            ("e", "\r\n"),
+        ]);
+
+        assert_eq!(read(input, &Dialect::csv()).unwrap(), expected);
+    }
+
+    #[test]
+    fn carriage_return() {
+        let input = "a\rb,1\r\nc,x\ry\r\nd,\"\r\"\ne,\"z\"\r";
+        let expected = keylist(vec![("a\rb", "1"), ("c", "x\ry"), ("d", "\r"), ("e", "z")]);
+
+        assert_eq!(read(input, &Dialect::csv()).unwrap(), expected);
+        assert_eq!(
+            read(&write(&expected, &Dialect::csv()), &Dialect::csv()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn header_and_delimiter() {
+        let input = "key\tvalue\na\t1,2\nb\t\"3\t4\"\n";
+        let expected = keylist(vec![("a", "1,2"), ("b", "3\t4")]);
+        let dialect = Dialect::tsv().with_header("key", "value");
+
+        assert_eq!(read(input, &dialect).unwrap(), expected);
+        assert_eq!(
+            write(&expected, &dialect),
+            "key\tvalue\r\na\t1,2\r\nb\t\"3\t4\"\r\n"
+        );
+        assert_eq!(read("", &dialect).unwrap(), VecKeylist::new());
+        assert_eq!(read("\nkey\tvalue\n", &dialect).unwrap(), VecKeylist::new());
+    }
+
+    #[test]
+    fn wrong_header() {
+        let dialect = Dialect::csv().with_header("key", "value");
+
+        match read("a,1\nb,2\n", &dialect) {
+            Err(Error::Parse { line, kind }) => assert_eq!((line, kind), (1, ErrorKind::Header)),
+            x => panic!("expected header error, got {:?}", x),
+        }
+        assert_eq!(
+            read("\n\"key\",value\nb,2\n", &dialect).unwrap(),
+            keylist(vec![("b", "2")])
+        );
+        assert_eq!(
+            read("value,key\n", &dialect).unwrap_err().to_string(),
+            "line 1: expected the header"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = keylist(vec![
+            ("a", "1"),
+            ("a", ""),
+            ("", "x"),
+            ("quote\"", "line\r\nbreak"),
+            ("semi;colon", "comma,"),
+        ]);
+
+        for dialect in &[
+            Dialect::csv(),
+            Dialect::tsv(),
+            Dialect {
+                delimiter: ';',
+                header: None,
+            },
+        ] {
+            assert_eq!(read(&write(&input, dialect), dialect).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn typed() {
+        let keylist: VecKeylist<u8, f64> =
+            VecKeylist::from_csv("1,0.5\n2,1e3\n1,-2".as_bytes(), &Dialect::csv()).unwrap();
+
+        assert_eq!(keylist, VecKeylist(vec![(1, 0.5), (2, 1000.0), (1, -2.0)]));
+
+        let result: Result<VecKeylist<String, u8>, _> =
+            VecKeylist::from_csv("a,1\nb,256".as_bytes(), &Dialect::csv());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 2: invalid value: number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("a,1\nb"), (2, ErrorKind::FieldCount(1)));
+        assert_eq!(error("a,1,2"), (1, ErrorKind::FieldCount(3)));
+        assert_eq!(
+            error("a,1\nb,\"open\n\nmore"),
+            (2, ErrorKind::UnterminatedQuote)
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let mut rows = pairs::<_, String, i32>("a,1\nb,x\nc,3".as_bytes(), &Dialect::csv());
+
+        assert_eq!(rows.next().unwrap().unwrap(), ("a".to_string(), 1));
+        assert!(rows.next().unwrap().is_err());
+        assert_eq!(rows.next().unwrap().unwrap(), ("c".to_string(), 3));
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn hash_keylist() {
+        let keylist: HashKeylist<String, i32, RandomState> =
+            HashKeylist::from_csv("a,1\nb,2\na,3".as_bytes(), &Dialect::csv()).unwrap();
+
+        assert_eq!(Some(&vec![1, 3]), keylist.get_all(&"a".to_string()));
+
+        let mut output = Vec::new();
+        keylist.write_csv(&mut output, &Dialect::csv()).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "a,1\r\nb,2\r\na,3\r\n");
+    }
+}
@@ -0,0 +1,322 @@
+//! Formatting keylists for humans and logs.
+//!
+//! Keys and values are formatted with `Display`, which does not tell a string `"3"` from a number,
+//! so `Style::Elixir` and `Style::Json` quote every value. `Style::ElixirScalars` and `Style::JsonScalars`
+//! write values that look like a number, a boolean or nil/null bare, for keylists whose values are those types.
+//! ```
+//! use keylist::display::Style;
+//! use keylist::VecKeylist;
+//!
+//! let keylist = VecKeylist(vec![("user", "alice"), ("attempts", "3"), ("user", "bob smith")]);
+//!
+//! assert_eq!(keylist.to_string(), r#"[user: "alice", attempts: "3", user: "bob smith"]"#);
+//! assert_eq!(
+//!     keylist.display_with(Style::Json).to_string(),
+//!     r#"{"user":"alice","attempts":"3","user":"bob smith"}"#
+//! );
+//! assert_eq!(
+//!     VecKeylist(vec![("attempts", 3), ("limit", 5)]).display_with(Style::JsonScalars).to_string(),
+//!     r#"{"attempts":3,"limit":5}"#
+//! );
+//! assert_eq!(
+//!     keylist.display_with(Style::KeyEquals).to_string(),
+//!     r#"user=alice attempts=3 user="bob smith""#
+//! );
+//! assert_eq!(
+//!     keylist.display_with(Style::Table).to_string(),
+//!     "user     | alice\nattempts | 3\nuser     | bob smith"
+//! );
+//! ```
+
+use crate::elixir::{write_key, write_string};
+use crate::number::number_parts;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+use crate::HashKeylist;
+use crate::VecKeylist;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Write};
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+use core::hash::{BuildHasher, Hash};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// keyword list literal with string values: `[a: "1", b: "x"]`
+    Elixir,
+    /// keyword list literal, values that look like a number, boolean or nil are bare: `[a: 1, b: "x"]`
+    ElixirScalars,
+    /// JSON object with string values, duplicate keys are kept: `{"a":"1","b":"x"}`
+    Json,
+    /// JSON object, values that look like a number, boolean or null are bare: `{"a":1,"b":"x"}`
+    JsonScalars,
+    /// logfmt: `a=1 b=x`
+    KeyEquals,
+    /// two aligned columns, one pair per line
+    Table,
+}
+
+/// Adapter returned by `display_with`.
+#[derive(Debug)]
+pub struct DisplayWith<'a, K, V> {
+    pairs: Vec<(&'a K, &'a V)>,
+    style: Style,
+}
+
+fn write_json_string<W: Write>(w: &mut W, string: &str) -> fmt::Result {
+    w.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+/// Quotes keys and values of `Style::KeyEquals` when they are empty or would be split on.
+fn write_key_equals_text(f: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    if text.is_empty()
+        || text.contains(|c: char| c.is_whitespace() || c.is_control() || c == '=' || c == '"')
+    {
+        write_json_string(f, text)
+    } else {
+        f.write_str(text)
+    }
+}
+
+impl<'a, K, V> DisplayWith<'a, K, V> {
+    pub(crate) fn new(pairs: Vec<(&'a K, &'a V)>, style: Style) -> Self {
+        DisplayWith { pairs, style }
+    }
+}
+
+impl<'a, K: Display, V: Display> DisplayWith<'a, K, V> {
+    fn elixir(&self, f: &mut fmt::Formatter, scalars: bool) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, (k, v)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write_key(f, &k.to_string())?;
+            f.write_char(' ')?;
+            let value = v.to_string();
+            match number_parts(&value) {
+                _ if !scalars => write_string(f, &value)?,
+                // Elixir needs a fraction before the exponent
+                Some((fraction, exponent)) if fraction || !exponent => f.write_str(&value)?,
+                _ if matches!(value.as_str(), "true" | "false" | "nil") => f.write_str(&value)?,
+                _ => write_string(f, &value)?,
+            }
+        }
+        f.write_char(']')
+    }
+
+    fn json(&self, f: &mut fmt::Formatter, scalars: bool) -> fmt::Result {
+        f.write_char('{')?;
+        for (i, (k, v)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write_json_string(f, &k.to_string())?;
+            f.write_char(':')?;
+            let value = v.to_string();
+            if scalars
+                && (number_parts(&value).is_some()
+                    || matches!(value.as_str(), "true" | "false" | "null"))
+            {
+                f.write_str(&value)?;
+            } else {
+                write_json_string(f, &value)?;
+            }
+        }
+        f.write_char('}')
+    }
+
+    fn key_equals(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (k, v)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            write_key_equals_text(f, &k.to_string())?;
+            f.write_char('=')?;
+            write_key_equals_text(f, &v.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn table(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keys: Vec<String> = self.pairs.iter().map(|(k, _)| k.to_string()).collect();
+        let width = keys.iter().map(|k| k.chars().count()).max().unwrap_or(0);
+        for (i, (key, (_, v))) in keys.iter().zip(&self.pairs).enumerate() {
+            if i > 0 {
+                f.write_char('\n')?;
+            }
+            write!(f, "{:width$} | {}", key, v, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, K: Display, V: Display> Display for DisplayWith<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.style {
+            Style::Elixir => self.elixir(f, false),
+            Style::ElixirScalars => self.elixir(f, true),
+            Style::Json => self.json(f, false),
+            Style::JsonScalars => self.json(f, true),
+            Style::KeyEquals => self.key_equals(f),
+            Style::Table => self.table(f),
+        }
+    }
+}
+
+impl<K, V> VecKeylist<K, V> {
+    pub fn display_with(&self, style: Style) -> DisplayWith<'_, K, V> {
+        DisplayWith::new(self.iter().map(|(k, v)| (k, v)).collect(), style)
+    }
+}
+
+/// Same as `display_with(Style::Elixir)`.
+impl<K: Display, V: Display> Display for VecKeylist<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_with(Style::Elixir).fmt(f)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    pub fn display_with(&self, style: Style) -> DisplayWith<'_, K, V> {
+        DisplayWith::new(self.iter().collect(), style)
+    }
+}
+
+/// Same as `display_with(Style::Elixir)`.
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+impl<K, V, S> Display for HashKeylist<K, V, S>
+where
+    K: Display + Hash + Eq,
+    V: Display + Eq,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_with(Style::Elixir).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Style;
+    use crate::VecKeylist;
+
+    fn keylist() -> VecKeylist<&'static str, &'static str> {
+        VecKeylist(vec![
+            ("int", "-12"),
+            ("float", "1.5e3"),
+            ("exp", "1e3"),
+            ("bool", "true"),
+            ("nil", "nil"),
+            ("null", "null"),
+            ("zero", "007"),
+            ("quote", "say \"hi\"\n"),
+            ("with space", ""),
+        ])
+    }
+
+    #[test]
+    fn elixir() {
+        assert_eq!(
+            keylist().display_with(Style::Elixir).to_string(),
+            r#"[int: "-12", float: "1.5e3", exp: "1e3", bool: "true", nil: "nil", null: "null", zero: "007", quote: "say \"hi\"\n", "with space": ""]"#
+        );
+        assert_eq!(
+            keylist().display_with(Style::ElixirScalars).to_string(),
+            r#"[int: -12, float: 1.5e3, exp: "1e3", bool: true, nil: nil, null: "null", zero: "007", quote: "say \"hi\"\n", "with space": ""]"#
+        );
+        assert_eq!(VecKeylist::<u8, u8>::new().to_string(), "[]");
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            keylist().display_with(Style::Json).to_string(),
+            r#"{"int":"-12","float":"1.5e3","exp":"1e3","bool":"true","nil":"nil","null":"null","zero":"007","quote":"say \"hi\"\n","with space":""}"#
+        );
+        assert_eq!(
+            keylist().display_with(Style::JsonScalars).to_string(),
+            r#"{"int":-12,"float":1.5e3,"exp":1e3,"bool":true,"nil":"nil","null":null,"zero":"007","quote":"say \"hi\"\n","with space":""}"#
+        );
+        assert_eq!(
+            VecKeylist::<u8, u8>::new()
+                .display_with(Style::Json)
+                .to_string(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn key_equals() {
+        assert_eq!(
+            keylist().display_with(Style::KeyEquals).to_string(),
+            r#"int=-12 float=1.5e3 exp=1e3 bool=true nil=nil null=null zero=007 quote="say \"hi\"\n" "with space"="""#
+        );
+        assert_eq!(
+            VecKeylist(vec![("a", "x=y"), ("b", "tab\t")])
+                .display_with(Style::KeyEquals)
+                .to_string(),
+            r#"a="x=y" b="tab\t""#
+        );
+        assert_eq!(
+            VecKeylist(vec![("a=b", 1), ("", 2), ("\"q\"", 3)])
+                .display_with(Style::KeyEquals)
+                .to_string(),
+            r#""a=b"=1 ""=2 "\"q\""=3"#
+        );
+    }
+
+    #[test]
+    fn table() {
+        let keylist = VecKeylist(vec![("ü", 1), ("name", 2), ("ab", 3)]);
+
+        assert_eq!(
+            keylist.display_with(Style::Table).to_string(),
+            "ü    | 1\nname | 2\nab   | 3"
+        );
+        assert_eq!(
+            VecKeylist::<u8, u8>::new()
+                .display_with(Style::Table)
+                .to_string(),
+            ""
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "hashbrown"))]
+    #[test]
+    fn hash_keylist() {
+        use crate::HashKeylist;
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+
+        let mut keylist = HashKeylist::with_hasher(BuildHasherDefault::<DefaultHasher>::default());
+        keylist.extend(vec![("a", 1), ("b", 2), ("a", 3)]);
+
+        assert_eq!(keylist.to_string(), r#"[a: "1", b: "2", a: "3"]"#);
+        assert_eq!(
+            keylist.display_with(Style::ElixirScalars).to_string(),
+            "[a: 1, b: 2, a: 3]"
+        );
+        assert_eq!(
+            keylist.display_with(Style::JsonScalars).to_string(),
+            r#"{"a":1,"b":2,"a":3}"#
+        );
+    }
+}
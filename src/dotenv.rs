@@ -0,0 +1,537 @@
+//! `.env` files and environment variables.
+//!
+//! Supports comments, `export` prefixes, single and double quotes, escape sequences in double quotes,
+//! values over multiple lines and `${VAR}` interpolation of earlier entries.
+//! ```
+//! use keylist::VecKeylist;
+//!
+//! let input = r#"
+//! ## database
+//! export HOST=localhost
+//! PORT=5432 # default port
+//! URL="postgres://${HOST}:${PORT}/app"
+//! MOTD='literal ${HOST}'
+//! "#;
+//!
+//! let keylist = VecKeylist::from_dotenv(input.as_bytes()).unwrap();
+//!
+//! assert_eq!(keylist.get(&"URL".to_string()).unwrap(), "postgres://localhost:5432/app");
+//! assert_eq!(keylist.get(&"MOTD".to_string()).unwrap(), "literal ${HOST}");
+//! ```
+
+use crate::VecKeylist;
+use std::ffi::OsString;
+use std::fmt;
+use std::io::{self, Read};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidKey,
+    MissingEquals,
+    UnterminatedQuote,
+    /// a `${` without closing `}`
+    UnterminatedVariable,
+    /// characters after a closing quote that are not a comment
+    TrailingCharacters,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::InvalidKey => f.write_str("invalid key"),
+            ErrorKind::MissingEquals => f.write_str("expected '=' after key"),
+            ErrorKind::UnterminatedQuote => f.write_str("unterminated quote"),
+            ErrorKind::UnterminatedVariable => f.write_str("unterminated variable"),
+            ErrorKind::TrailingCharacters => f.write_str("trailing characters after value"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// error with the 1-based line number
+    Parse {
+        line: usize,
+        kind: ErrorKind,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => e.fmt(f),
+            Error::Parse { line, kind } => write!(f, "line {}: {}", line, kind),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    keylist: VecKeylist<String, String>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: ErrorKind) -> Error {
+        Error::Parse {
+            line: self.line,
+            kind,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_blanks(&mut self) {
+        while matches!(self.peek(), Some(' ') | Some('\t')) {
+            self.bump();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while !matches!(self.bump(), None | Some('\n')) {}
+    }
+
+    /// Allows whitespace and a comment up to the end of the line.
+    fn end_of_line(&mut self) -> Result<(), Error> {
+        self.skip_blanks();
+        match self.peek() {
+            None | Some('\n') | Some('#') => {
+                self.skip_line();
+                Ok(())
+            }
+            Some('\r') if self.input[self.pos..].starts_with("\r\n") => {
+                self.skip_line();
+                Ok(())
+            }
+            _ => Err(self.error(ErrorKind::TrailingCharacters)),
+        }
+    }
+
+    fn key(&mut self) -> &'a str {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if is_key_char(c)) {
+            self.bump();
+        }
+        &self.input[start..self.pos]
+    }
+
+    /// Reads a shell variable name, `[A-Za-z_][A-Za-z0-9_]*`, unlike keys it stops at `.` and `-`.
+    fn variable_name(&mut self) -> &'a str {
+        let start = self.pos;
+        if matches!(self.peek(), Some(c) if c.is_ascii_alphabetic() || c == '_') {
+            while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_') {
+                self.bump();
+            }
+        }
+        &self.input[start..self.pos]
+    }
+
+    /// The value of an earlier entry, the last one wins just like in a shell.
+    fn lookup(&self, name: &str) -> Option<&str> {
+        self.keylist
+            .0
+            .iter()
+            .rev()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Parses `$VAR`, `${VAR}` or `${VAR:-default}`, the `$` is already consumed.
+    fn variable(&mut self, buffer: &mut String) -> Result<(), Error> {
+        if self.peek() != Some('{') {
+            let name = self.variable_name();
+            if name.is_empty() {
+                buffer.push('$');
+            } else {
+                buffer.push_str(self.lookup(name).unwrap_or_default());
+            }
+            return Ok(());
+        }
+
+        self.bump();
+        let rest = &self.input[self.pos..];
+        let end = rest
+            .find(['}', '\n'])
+            .filter(|&x| rest[x..].starts_with('}'))
+            .ok_or_else(|| self.error(ErrorKind::UnterminatedVariable))?;
+        let (name, default) = match rest[..end].find(":-") {
+            Some(pos) => (&rest[..pos], Some(&rest[pos + 2..end])),
+            None => (&rest[..end], None),
+        };
+        let value = match (self.lookup(name), default) {
+            (Some(value), _) if !value.is_empty() => value,
+            (_, Some(default)) => default,
+            (value, None) => value.unwrap_or_default(),
+        };
+        buffer.push_str(value);
+        self.pos += end + 1;
+        Ok(())
+    }
+
+    fn single_quoted(&mut self) -> Result<String, Error> {
+        let start_line = self.line;
+        let mut buffer = String::new();
+        loop {
+            match self.bump() {
+                Some('\'') => return Ok(buffer),
+                Some(c) => buffer.push(c),
+                None => {
+                    return Err(Error::Parse {
+                        line: start_line,
+                        kind: ErrorKind::UnterminatedQuote,
+                    })
+                }
+            }
+        }
+    }
+
+    fn double_quoted(&mut self) -> Result<String, Error> {
+        let start_line = self.line;
+        let mut buffer = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(buffer),
+                Some('\\') => match self.bump() {
+                    Some('n') => buffer.push('\n'),
+                    Some('r') => buffer.push('\r'),
+                    Some('t') => buffer.push('\t'),
+                    Some(c @ '"') | Some(c @ '\\') | Some(c @ '$') => buffer.push(c),
+                    Some(c) => {
+                        buffer.push('\\');
+                        buffer.push(c);
+                    }
+                    None => break,
+                },
+                Some('$') => self.variable(&mut buffer)?,
+                Some(c) => buffer.push(c),
+                None => break,
+            }
+        }
+        Err(Error::Parse {
+            line: start_line,
+            kind: ErrorKind::UnterminatedQuote,
+        })
+    }
+
+    fn unquoted(&mut self) -> Result<String, Error> {
+        let mut buffer = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '\n' => break,
+                // like in a shell only a `#` after a blank starts a comment, blanks after the `=` count too
+                '#' if self.input[..self.pos].ends_with([' ', '\t']) => break,
+                '$' => {
+                    self.bump();
+                    self.variable(&mut buffer)?;
+                }
+                c => {
+                    buffer.push(c);
+                    self.bump();
+                }
+            }
+        }
+        self.skip_line();
+        buffer.truncate(buffer.trim_end().len());
+        Ok(buffer)
+    }
+
+    fn entry(&mut self) -> Result<(), Error> {
+        if self.input[self.pos..].starts_with("export ") {
+            self.pos += "export".len();
+            self.skip_blanks();
+        }
+        let key = self.key();
+        if key.is_empty() || key.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(self.error(ErrorKind::InvalidKey));
+        }
+        self.skip_blanks();
+        if self.bump() != Some('=') {
+            return Err(self.error(ErrorKind::MissingEquals));
+        }
+        self.skip_blanks();
+        let value = match self.peek() {
+            Some('\'') => {
+                self.bump();
+                let value = self.single_quoted()?;
+                self.end_of_line()?;
+                value
+            }
+            Some('"') => {
+                self.bump();
+                let value = self.double_quoted()?;
+                self.end_of_line()?;
+                value
+            }
+            _ => self.unquoted()?,
+        };
+        self.keylist.push(key.to_string(), value);
+        Ok(())
+    }
+
+    fn parse(mut self) -> Result<VecKeylist<String, String>, Error> {
+        loop {
+            while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+                self.bump();
+            }
+            match self.peek() {
+                None => return Ok(self.keylist),
+                Some('#') => self.skip_line(),
+                Some(_) => self.entry()?,
+            }
+        }
+    }
+}
+
+/// Parses the contents of a `.env` file.
+pub fn parse(input: &str) -> Result<VecKeylist<String, String>, Error> {
+    Parser {
+        input,
+        pos: 0,
+        line: 1,
+        keylist: VecKeylist::new(),
+    }
+    .parse()
+}
+
+fn from_vars_prefixed<I>(vars: I, prefix: &str, lowercase: bool) -> VecKeylist<String, String>
+where
+    I: IntoIterator<Item = (OsString, OsString)>,
+{
+    vars.into_iter()
+        .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+        .filter_map(|(k, v)| {
+            let key = k.strip_prefix(prefix)?;
+            let key = if lowercase {
+                key.to_lowercase()
+            } else {
+                key.to_string()
+            };
+            Some((key, v))
+        })
+        .collect()
+}
+
+impl VecKeylist<String, String> {
+    /// Reads a `.env` file, see the [module documentation](crate::dotenv) for the supported syntax.
+    pub fn from_dotenv<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        parse(&input)
+    }
+
+    /// All environment variables starting with the prefix, with the prefix stripped from the keys.
+    ///
+    /// Variables that are not valid unicode are skipped.
+    pub fn from_env_prefixed(prefix: &str) -> Self {
+        from_vars_prefixed(std::env::vars_os(), prefix, false)
+    }
+
+    /// Same as `from_env_prefixed`, but the keys are lowercased: `APP_LOG_LEVEL` becomes `log_level`.
+    pub fn from_env_prefixed_lowercase(prefix: &str) -> Self {
+        from_vars_prefixed(std::env::vars_os(), prefix, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_vars_prefixed, parse, Error, ErrorKind};
+    use crate::VecKeylist;
+    use std::ffi::OsString;
+
+    fn keylist(pairs: Vec<(&str, &str)>) -> VecKeylist<String, String> {
+        pairs
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn error(input: &str) -> (usize, ErrorKind) {
+        match parse(input) {
+            Err(Error::Parse { line, kind }) => (line, kind),
+            x => panic!("expected parse error, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let input = "A=1\nB = two words  \n\n# comment\n  C=\nexport D=4\nA=5\r\n";
+        let expected = keylist(vec![
+            ("A", "1"),
+            ("B", "two words"),
+            ("C", ""),
+            ("D", "4"),
+            ("A", "5"),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn comments() {
+        let input = "A=a#b\nB=a #b\nC='x' # y\nD=\"x\"#y\nE= # c\nF=#c\n";
+        let expected = keylist(vec![
+            ("A", "a#b"),
+            ("B", "a"),
+            ("C", "x"),
+            ("D", "x"),
+            ("E", ""),
+            ("F", "#c"),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn quotes() {
+        let input = r#"
+SINGLE='its \n ${A}'
+DOUBLE="tab\there \"quoted\" \$HOME \\ \q"
+EMPTY=""
+"#;
+        let expected = keylist(vec![
+            ("SINGLE", "its \\n ${A}"),
+            ("DOUBLE", "tab\there \"quoted\" $HOME \\ \\q"),
+            ("EMPTY", ""),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn multi_line() {
+        let input = "KEY=\"-----BEGIN-----\nabc\n-----END-----\"\nPLAIN='a\nb'\nNEXT=1";
+        let expected = keylist(vec![
+            ("KEY", "-----BEGIN-----\nabc\n-----END-----"),
+            ("PLAIN", "a\nb"),
+            ("NEXT", "1"),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn interpolation() {
+        let input = r#"
+HOST=localhost
+PORT=80
+URL=http://${HOST}:$PORT/
+QUOTED="${HOST}/$MISSING/${MISSING:-fallback}/${HOST:-unused}"
+HOST=example.com
+LATER=${HOST} costs $5
+DOTTED=$HOST.com
+DASHED="$HOST-1"
+"#;
+        let expected = keylist(vec![
+            ("HOST", "localhost"),
+            ("PORT", "80"),
+            ("URL", "http://localhost:80/"),
+            ("QUOTED", "localhost//fallback/localhost"),
+            ("HOST", "example.com"),
+            // there are no positional parameters, a `$` without a variable name stays as it is
+            ("LATER", "example.com costs $5"),
+            ("DOTTED", "example.com.com"),
+            ("DASHED", "example.com-1"),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+        assert_eq!(
+            parse("A=$ and $$").unwrap(),
+            keylist(vec![("A", "$ and $$")])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("A=1\n=2"), (2, ErrorKind::InvalidKey));
+        assert_eq!(error("1A=2"), (1, ErrorKind::InvalidKey));
+        assert_eq!(error("A=1\nB 2"), (2, ErrorKind::MissingEquals));
+        assert_eq!(
+            error("A=1\nB='open\n\nC=1"),
+            (2, ErrorKind::UnterminatedQuote)
+        );
+        assert_eq!(error("A=\"open"), (1, ErrorKind::UnterminatedQuote));
+        assert_eq!(error("A=\"x\" y"), (1, ErrorKind::TrailingCharacters));
+        assert_eq!(error("A=${B\nC=1"), (1, ErrorKind::UnterminatedVariable));
+        assert_eq!(
+            parse("A").unwrap_err().to_string(),
+            "line 1: expected '=' after key"
+        );
+    }
+
+    #[test]
+    fn from_dotenv() {
+        let keylist = VecKeylist::from_dotenv("A=1\nB=2".as_bytes()).unwrap();
+
+        assert_eq!(keylist, self::keylist(vec![("A", "1"), ("B", "2")]));
+    }
+
+    #[test]
+    fn vars_prefixed() {
+        let vars = [
+            ("APP_LOG_LEVEL", "debug"),
+            ("PATH", "/bin"),
+            ("APP_PORT", "80"),
+            ("APP", "x"),
+        ];
+        let vars = || {
+            vars.iter()
+                .map(|(k, v)| (OsString::from(k), OsString::from(v)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            from_vars_prefixed(vars(), "APP_", false),
+            keylist(vec![("LOG_LEVEL", "debug"), ("PORT", "80")])
+        );
+        assert_eq!(
+            from_vars_prefixed(vars(), "APP_", true),
+            keylist(vec![("log_level", "debug"), ("port", "80")])
+        );
+    }
+
+    #[test]
+    fn from_env_prefixed() {
+        std::env::set_var("KEYLIST_TEST_DOTENV_Value", "1");
+
+        assert_eq!(
+            VecKeylist::from_env_prefixed("KEYLIST_TEST_DOTENV_"),
+            keylist(vec![("Value", "1")])
+        );
+        assert_eq!(
+            VecKeylist::from_env_prefixed_lowercase("KEYLIST_TEST_DOTENV_"),
+            keylist(vec![("value", "1")])
+        );
+    }
+}
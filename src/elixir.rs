@@ -0,0 +1,938 @@
+//! Parse and print Elixir keyword list literals.
+//!
+//! Understands the shorthand (`key: value`) and explicit tuple (`{:key, value}`) forms.
+//! Keys are the atom names without the leading colon.
+//! ```
+//! use keylist::elixir::Value;
+//! use keylist::VecKeylist;
+//!
+//! let keylist: VecKeylist<String, Value> =
+//!     r#"[timeout: 5_000, retries: 3, name: "svc"]"#.parse().unwrap();
+//!
+//! assert_eq!(keylist.get(&"timeout".to_string()), Some(&Value::Integer(5000)));
+//! assert_eq!(keylist.to_elixir().unwrap(), r#"[timeout: 5000, retries: 3, name: "svc"]"#);
+//! ```
+
+use crate::VecKeylist;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+/// How deep lists and tuples can be nested when parsing, deeper input is rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Nil,
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    /// atom name without the leading colon
+    Atom(String),
+    String(String),
+    /// keyword lists are lists of 2-tuples with an atom as first element, just like in Elixir
+    List(Vec<Value>),
+    Tuple(Vec<Value>),
+}
+
+impl Value {
+    /// Returns the keyword list, if this value is a list of `{atom, value}` tuples.
+    pub fn as_keylist(&self) -> Option<VecKeylist<String, Value>> {
+        match self {
+            Value::List(items) => items.iter().map(as_keyword).collect(),
+            _ => None,
+        }
+    }
+
+    /// Whether there are no NaN or infinite floats in the value, Elixir has no literal for those.
+    fn is_finite(&self) -> bool {
+        match self {
+            Value::Float(float) => float.is_finite(),
+            Value::List(items) | Value::Tuple(items) => items.iter().all(Value::is_finite),
+            _ => true,
+        }
+    }
+
+    fn is_keylist(&self) -> bool {
+        match self {
+            Value::List(items) => {
+                !items.is_empty() && items.iter().all(|x| as_keyword(x).is_some())
+            }
+            _ => false,
+        }
+    }
+}
+
+fn as_keyword(value: &Value) -> Option<(String, Value)> {
+    match value {
+        Value::Tuple(pair) => match pair.as_slice() {
+            [Value::Atom(key), value] => Some((key.clone(), value.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl From<VecKeylist<String, Value>> for Value {
+    fn from(keylist: VecKeylist<String, Value>) -> Self {
+        Value::List(
+            keylist
+                .into_iter()
+                .map(|(k, v)| Value::Tuple(vec![Value::Atom(k), v]))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_value(f, self, None)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEof,
+    UnexpectedChar(char),
+    UnknownIdentifier(String),
+    InvalidNumber,
+    IntegerOverflow,
+    InvalidEscape,
+    /// string interpolation can not be evaluated
+    Interpolation,
+    /// Elixir requires a space after the colon of a keyword, `[a:1]` is invalid
+    MissingSpaceAfterKeyword,
+    /// keywords are only allowed at the end of a list, `[a: 1, 2]` is invalid
+    KeywordNotLast,
+    /// the element is not a `{atom, value}` tuple
+    ExpectedKeyword,
+    ExpectedList,
+    TrailingCharacters,
+    /// lists and tuples are nested deeper than 128 levels
+    TooDeep,
+    /// a float is NaN or infinite, which can not be printed as an Elixir literal
+    NonFiniteFloat,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEof => f.write_str("unexpected end of input"),
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ErrorKind::UnknownIdentifier(name) => write!(f, "unknown identifier {:?}", name),
+            ErrorKind::InvalidNumber => f.write_str("invalid number"),
+            ErrorKind::IntegerOverflow => f.write_str("integer does not fit in an i64"),
+            ErrorKind::InvalidEscape => f.write_str("invalid escape sequence"),
+            ErrorKind::Interpolation => f.write_str("string interpolation is not supported"),
+            ErrorKind::MissingSpaceAfterKeyword => f.write_str("expected a space after keyword"),
+            ErrorKind::KeywordNotLast => f.write_str("keywords must be at the end of a list"),
+            ErrorKind::ExpectedKeyword => f.write_str("expected a keyword"),
+            ErrorKind::ExpectedList => f.write_str("expected a list"),
+            ErrorKind::TrailingCharacters => f.write_str("trailing characters after list"),
+            ErrorKind::TooDeep => f.write_str("lists and tuples are nested too deep"),
+            ErrorKind::NonFiniteFloat => f.write_str("float is NaN or infinite"),
+        }
+    }
+}
+
+/// Position is given as 1-based line and column (in characters) and the byte offset in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+impl FromStr for VecKeylist<String, Value> {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input, pos: 0 };
+        parser.skip_whitespace();
+        if parser.peek() != Some('[') {
+            return Err(parser.error(ErrorKind::ExpectedList));
+        }
+        let items = parser.list(0)?;
+        parser.skip_whitespace();
+        if parser.peek().is_some() {
+            return Err(parser.error(ErrorKind::TrailingCharacters));
+        }
+
+        items
+            .into_iter()
+            .map(|(pos, item)| {
+                as_keyword(&item).ok_or_else(|| parser.error_at(pos, ErrorKind::ExpectedKeyword))
+            })
+            .collect()
+    }
+}
+
+impl VecKeylist<String, Value> {
+    /// Prints the keylist on a single line.
+    ///
+    /// Fails with `ErrorKind::NonFiniteFloat` if a float is NaN or infinite, Elixir has no literal for those.
+    pub fn to_elixir(&self) -> Result<String, ErrorKind> {
+        self.print(None)
+    }
+
+    /// Prints every keyword on its own line, nested keyword lists are indented.
+    ///
+    /// Fails like `to_elixir` does.
+    pub fn to_elixir_pretty(&self) -> Result<String, ErrorKind> {
+        self.print(Some(0))
+    }
+
+    fn print(&self, indent: Option<usize>) -> Result<String, ErrorKind> {
+        if !self.values().all(Value::is_finite) {
+            return Err(ErrorKind::NonFiniteFloat);
+        }
+        let mut buffer = String::new();
+        write_keywords(&mut buffer, self.iter().map(|(k, v)| (k, v)), indent).unwrap();
+        Ok(buffer)
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '@'
+}
+
+fn is_identifier(name: &str) -> bool {
+    let name = name.strip_suffix(|c| c == '?' || c == '!').unwrap_or(name);
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if is_identifier_start(c) => chars.all(is_identifier_char),
+        _ => false,
+    }
+}
+
+pub(crate) fn write_string<W: Write>(w: &mut W, string: &str) -> fmt::Result {
+    w.write_char('"')?;
+    let mut chars = string.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            '#' if chars.peek() == Some(&'{') => w.write_str("\\#")?,
+            c if c.is_control() => write!(w, "\\u{{{:X}}}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+/// Writes `key:`, quoting the key when it is not a valid identifier.
+pub(crate) fn write_key<W: Write>(w: &mut W, key: &str) -> fmt::Result {
+    if is_identifier(key) {
+        w.write_str(key)?;
+    } else {
+        write_string(w, key)?;
+    }
+    w.write_char(':')
+}
+
+fn write_atom<W: Write>(w: &mut W, name: &str) -> fmt::Result {
+    w.write_char(':')?;
+    if is_identifier(name) {
+        w.write_str(name)
+    } else {
+        write_string(w, name)
+    }
+}
+
+fn write_float<W: Write>(w: &mut W, float: f64) -> fmt::Result {
+    // only `Display` gets here with these, it can not fail, so they are shown like the atoms Erlang uses for them
+    if float.is_nan() {
+        return w.write_str(":nan");
+    } else if float.is_infinite() {
+        return w.write_str(if float > 0.0 {
+            ":infinity"
+        } else {
+            ":neg_infinity"
+        });
+    }
+    // Elixir always wants a fraction before the exponent: 1.0e20 instead of 1e20
+    let text = format!("{:?}", float);
+    match text.find('e') {
+        Some(pos) if !text[..pos].contains('.') => {
+            write!(w, "{}.0{}", &text[..pos], &text[pos..])
+        }
+        _ => w.write_str(&text),
+    }
+}
+
+fn write_indent<W: Write>(w: &mut W, indent: usize) -> fmt::Result {
+    for _ in 0..indent {
+        w.write_str("  ")?;
+    }
+    Ok(())
+}
+
+fn write_keywords<'a, W, I>(w: &mut W, keywords: I, indent: Option<usize>) -> fmt::Result
+where
+    W: Write,
+    I: Iterator<Item = (&'a String, &'a Value)>,
+{
+    let mut keywords = keywords.peekable();
+    if keywords.peek().is_none() {
+        return w.write_str("[]");
+    }
+    w.write_char('[')?;
+    let mut first = true;
+    for (k, v) in keywords {
+        if !first {
+            w.write_char(',')?;
+        }
+        match indent {
+            Some(indent) => {
+                w.write_char('\n')?;
+                write_indent(w, indent + 1)?;
+            }
+            None if !first => w.write_char(' ')?,
+            None => (),
+        }
+        first = false;
+        write_key(w, k)?;
+        w.write_char(' ')?;
+        write_value(w, v, indent.map(|x| x + 1))?;
+    }
+    if let Some(indent) = indent {
+        w.write_char('\n')?;
+        write_indent(w, indent)?;
+    }
+    w.write_char(']')
+}
+
+fn write_items<W: Write>(w: &mut W, items: &[Value], indent: Option<usize>) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            w.write_str(", ")?;
+        }
+        write_value(w, item, indent)?;
+    }
+    Ok(())
+}
+
+fn write_value<W: Write>(w: &mut W, value: &Value, indent: Option<usize>) -> fmt::Result {
+    match value {
+        Value::Nil => w.write_str("nil"),
+        Value::Boolean(b) => write!(w, "{}", b),
+        Value::Integer(int) => write!(w, "{}", int),
+        Value::Float(float) => write_float(w, *float),
+        Value::Atom(name) => write_atom(w, name),
+        Value::String(string) => write_string(w, string),
+        Value::List(items) if value.is_keylist() => {
+            let keywords = items.iter().map(|item| match item {
+                Value::Tuple(pair) => match pair.as_slice() {
+                    [Value::Atom(k), v] => (k, v),
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            });
+            write_keywords(w, keywords, indent)
+        }
+        Value::List(items) => {
+            w.write_char('[')?;
+            write_items(w, items, indent)?;
+            w.write_char(']')
+        }
+        Value::Tuple(items) => {
+            w.write_char('{')?;
+            write_items(w, items, indent)?;
+            w.write_char('}')
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error_at(&self, offset: usize, kind: ErrorKind) -> ParseError {
+        let before = &self.input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|x| x + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        ParseError {
+            line,
+            column,
+            offset,
+            kind,
+        }
+    }
+
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        self.error_at(self.pos, kind)
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(c) => self.error(ErrorKind::UnexpectedChar(c)),
+            None => self.error(ErrorKind::UnexpectedEof),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.input[self.pos..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.peek() == Some(expected) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.bump();
+            } else if c == '#' {
+                while !matches!(self.bump(), None | Some('\n')) {}
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Parses a keyword key, returns `None` and does not consume anything if there is no key.
+    fn keyword(&mut self) -> Result<Option<String>, ParseError> {
+        let start = self.pos;
+        let key = match self.peek() {
+            Some(c) if is_identifier_start(c) => self.identifier(),
+            Some('"') => self.string()?,
+            _ => return Ok(None),
+        };
+        if self.peek() != Some(':') || self.peek_nth(1) == Some(':') {
+            self.pos = start;
+            return Ok(None);
+        }
+        self.bump();
+        match self.peek() {
+            Some(c) if c.is_whitespace() => Ok(Some(key)),
+            _ => Err(self.error(ErrorKind::MissingSpaceAfterKeyword)),
+        }
+    }
+
+    /// Parses the items of a list, together with the byte offset where each item starts.
+    fn list(&mut self, depth: usize) -> Result<Vec<(usize, Value)>, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep));
+        }
+        self.expect('[')?;
+        let mut items = Vec::new();
+        let mut seen_keyword = false;
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(items);
+            }
+            let start = self.pos;
+            if let Some(key) = self.keyword()? {
+                self.skip_whitespace();
+                let value = self.value(depth)?;
+                items.push((start, Value::Tuple(vec![Value::Atom(key), value])));
+                seen_keyword = true;
+            } else if seen_keyword {
+                return Err(self.error(ErrorKind::KeywordNotLast));
+            } else {
+                items.push((start, self.value(depth)?));
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => (),
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn tuple(&mut self, depth: usize) -> Result<Vec<Value>, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep));
+        }
+        self.expect('{')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.bump();
+                return Ok(items);
+            }
+            items.push(self.value(depth)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some('}') => (),
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, ParseError> {
+        match self.peek() {
+            Some('[') => Ok(Value::List(
+                self.list(depth + 1)?.into_iter().map(|(_, x)| x).collect(),
+            )),
+            Some('{') => Ok(Value::Tuple(self.tuple(depth + 1)?)),
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some(':') => {
+                self.bump();
+                match self.peek() {
+                    Some('"') => Ok(Value::Atom(self.string()?)),
+                    Some(c) if is_identifier_start(c) => Ok(Value::Atom(self.identifier())),
+                    _ => Err(self.unexpected()),
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '-' => self.number(),
+            Some(c) if is_identifier_start(c) => {
+                let start = self.pos;
+                match self.identifier().as_str() {
+                    "nil" => Ok(Value::Nil),
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    name => {
+                        Err(self.error_at(start, ErrorKind::UnknownIdentifier(name.to_string())))
+                    }
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if is_identifier_char(c)) {
+            self.bump();
+        }
+        if matches!(self.peek(), Some('?') | Some('!')) {
+            self.bump();
+        }
+        self.input[start..self.pos].to_string()
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut buffer = String::new();
+        loop {
+            let start = self.pos;
+            match self.bump() {
+                None => return Err(self.error(ErrorKind::UnexpectedEof)),
+                Some('"') => return Ok(buffer),
+                Some('#') if self.peek() == Some('{') => {
+                    return Err(self.error_at(start, ErrorKind::Interpolation))
+                }
+                Some('\\') => buffer.push(self.escape(start)?),
+                Some(c) => buffer.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self, start: usize) -> Result<char, ParseError> {
+        let c = match self.bump() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('s') => ' ',
+            Some('0') => '\0',
+            Some('a') => '\x07',
+            Some('b') => '\x08',
+            Some('d') => '\x7f',
+            Some('e') => '\x1b',
+            Some('f') => '\x0c',
+            Some('v') => '\x0b',
+            Some('x') => {
+                let digits = self.take_while(|c| c.is_ascii_hexdigit(), 2);
+                return self.code_point(start, digits, 2);
+            }
+            Some('u') if self.peek() == Some('{') => {
+                self.bump();
+                let digits = self.take_while(|c| c.is_ascii_hexdigit(), 6);
+                if self.bump() != Some('}') {
+                    return Err(self.error_at(start, ErrorKind::InvalidEscape));
+                }
+                return self.code_point(start, digits, 1);
+            }
+            Some('u') => {
+                let digits = self.take_while(|c| c.is_ascii_hexdigit(), 4);
+                return self.code_point(start, digits, 4);
+            }
+            Some(c) => c,
+            None => return Err(self.error(ErrorKind::UnexpectedEof)),
+        };
+        Ok(c)
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F, max: usize) -> &'a str {
+        let start = self.pos;
+        let mut count = 0;
+        while count < max && matches!(self.peek(), Some(c) if f(c)) {
+            self.bump();
+            count += 1;
+        }
+        &self.input[start..self.pos]
+    }
+
+    fn code_point(&self, start: usize, digits: &str, min: usize) -> Result<char, ParseError> {
+        if digits.len() < min {
+            return Err(self.error_at(start, ErrorKind::InvalidEscape));
+        }
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(core::char::from_u32)
+            .ok_or_else(|| self.error_at(start, ErrorKind::InvalidEscape))
+    }
+
+    /// Digits with underscores between them, like `5_000`.
+    fn digits(&mut self, radix: u32) -> Result<String, ParseError> {
+        let mut digits = String::new();
+        loop {
+            match self.peek() {
+                Some(c) if c.is_digit(radix) => {
+                    digits.push(c);
+                    self.bump();
+                }
+                Some('_')
+                    if !digits.is_empty()
+                        && matches!(self.peek_nth(1), Some(c) if c.is_digit(radix)) =>
+                {
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+        if digits.is_empty() {
+            return Err(self.error(ErrorKind::InvalidNumber));
+        }
+        Ok(digits)
+    }
+
+    fn end_of_number(&self) -> Result<(), ParseError> {
+        if matches!(self.peek(), Some(c) if is_identifier_char(c)) {
+            return Err(self.error(ErrorKind::InvalidNumber));
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<Value, ParseError> {
+        let start = self.pos;
+        let negative = self.peek() == Some('-');
+        if negative {
+            self.bump();
+        }
+        let sign = if negative { "-" } else { "" };
+
+        let radix = match (self.peek(), self.peek_nth(1)) {
+            (Some('0'), Some('x')) => 16,
+            (Some('0'), Some('o')) => 8,
+            (Some('0'), Some('b')) => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.bump();
+            self.bump();
+            let digits = self.digits(radix)?;
+            self.end_of_number()?;
+            return i64::from_str_radix(&format!("{}{}", sign, digits), radix)
+                .map(Value::Integer)
+                .map_err(|_| self.error_at(start, ErrorKind::IntegerOverflow));
+        }
+
+        let mut text = format!("{}{}", sign, self.digits(10)?);
+        if self.peek() == Some('.') && matches!(self.peek_nth(1), Some(c) if c.is_ascii_digit()) {
+            self.bump();
+            text.push('.');
+            text.push_str(&self.digits(10)?);
+            if matches!(self.peek(), Some('e') | Some('E')) {
+                self.bump();
+                text.push('e');
+                if let Some(c @ '-') | Some(c @ '+') = self.peek() {
+                    self.bump();
+                    text.push(c);
+                }
+                text.push_str(&self.digits(10)?);
+            }
+            self.end_of_number()?;
+            return text
+                .parse()
+                .map(Value::Float)
+                .map_err(|_| self.error_at(start, ErrorKind::InvalidNumber));
+        }
+
+        self.end_of_number()?;
+        text.parse()
+            .map(Value::Integer)
+            .map_err(|_| self.error_at(start, ErrorKind::IntegerOverflow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorKind, ParseError, Value};
+    use crate::VecKeylist;
+
+    fn parse(input: &str) -> Result<VecKeylist<String, Value>, ParseError> {
+        input.parse()
+    }
+
+    fn keylist(pairs: Vec<(&str, Value)>) -> VecKeylist<String, Value> {
+        pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+    }
+
+    fn error(input: &str) -> (usize, usize, ErrorKind) {
+        let err = parse(input).unwrap_err();
+        (err.line, err.column, err.kind)
+    }
+
+    #[test]
+    fn shorthand() {
+        let expected = keylist(vec![
+            ("timeout", Value::Integer(5000)),
+            ("retries", Value::Integer(3)),
+            ("name", Value::String("svc".to_string())),
+        ]);
+
+        assert_eq!(
+            parse(r#"[timeout: 5_000, retries: 3, name: "svc"]"#),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn tuple_form() {
+        let expected = keylist(vec![
+            ("a", Value::Integer(1)),
+            ("b c", Value::Atom("ok".to_string())),
+            ("d", Value::Nil),
+        ]);
+
+        assert_eq!(parse(r#"[{:a, 1}, {:"b c", :ok}, d: nil]"#), Ok(expected));
+    }
+
+    #[test]
+    fn scalars() {
+        let expected = keylist(vec![
+            ("yes", Value::Boolean(true)),
+            ("no", Value::Boolean(false)),
+            ("neg", Value::Integer(-12)),
+            ("hex", Value::Integer(255)),
+            ("float", Value::Float(1000.5)),
+            ("exp", Value::Float(-1.5e-3)),
+            ("atom", Value::Atom("info".to_string())),
+            ("quoted key", Value::String("a\"b\n\u{e9}".to_string())),
+            ("valid?", Value::Atom("Elixir.Foo".to_string())),
+        ]);
+
+        assert_eq!(
+            parse(
+                r#"[
+                    yes: true,
+                    no: false,
+                    neg: -12,
+                    hex: 0xFF,
+                    float: 1_000.5,
+                    exp: -1.5e-3,
+                    atom: :info,
+                    "quoted key": "a\"b\né",
+                    valid?: :"Elixir.Foo",
+                ]"#
+            ),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let inner = keylist(vec![("debug", Value::Boolean(true))]);
+        let expected = keylist(vec![
+            ("opts", Value::from(inner.clone())),
+            (
+                "ports",
+                Value::List(vec![Value::Integer(1), Value::Integer(2)]),
+            ),
+            (
+                "mixed",
+                Value::List(vec![
+                    Value::Integer(1),
+                    Value::Tuple(vec![Value::Atom("a".to_string()), Value::Integer(2)]),
+                ]),
+            ),
+            ("empty", Value::List(vec![])),
+        ]);
+
+        let parsed =
+            parse("[opts: [debug: true], ports: [1, 2], mixed: [1, a: 2], empty: []]").unwrap();
+
+        assert_eq!(parsed, expected);
+        assert_eq!(
+            parsed.get(&"opts".to_string()).unwrap().as_keylist(),
+            Some(inner)
+        );
+    }
+
+    #[test]
+    fn comments_and_empty() {
+        assert_eq!(parse("  [] "), Ok(VecKeylist::new()));
+        assert_eq!(
+            parse("[\n  # the answer\n  a: 42 # inline\n]"),
+            Ok(keylist(vec![("a", Value::Integer(42))]))
+        );
+    }
+
+    #[test]
+    fn print() {
+        let input = keylist(vec![
+            ("timeout", Value::Integer(5000)),
+            ("ratio", Value::Float(1e20)),
+            ("name", Value::String("#{x}\t".to_string())),
+            ("my key", Value::Atom("with space".to_string())),
+            (
+                "opts",
+                Value::from(keylist(vec![("a", Value::Nil), ("b", Value::Float(0.5))])),
+            ),
+            (
+                "t",
+                Value::Tuple(vec![Value::Boolean(true), Value::List(vec![])]),
+            ),
+        ]);
+        let expected = r#"[timeout: 5000, ratio: 1.0e20, name: "\#{x}\t", "my key": :"with space", opts: [a: nil, b: 0.5], t: {true, []}]"#;
+
+        assert_eq!(input.to_elixir().unwrap(), expected);
+        assert_eq!(parse(expected), Ok(input));
+    }
+
+    #[test]
+    fn print_pretty() {
+        let input = parse("[a: 1, opts: [b: [1, 2], c: [d: :e]], f: []]").unwrap();
+        let expected = "[
+  a: 1,
+  opts: [
+    b: [1, 2],
+    c: [
+      d: :e
+    ]
+  ],
+  f: []
+]";
+
+        assert_eq!(input.to_elixir_pretty().unwrap(), expected);
+        assert_eq!(parse(expected), Ok(input));
+        assert_eq!(VecKeylist::new().to_elixir_pretty().unwrap(), "[]");
+    }
+
+    #[test]
+    fn print_non_finite_float() {
+        for float in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let input = keylist(vec![("a", Value::Tuple(vec![Value::Float(*float)]))]);
+
+            assert_eq!(input.to_elixir(), Err(ErrorKind::NonFiniteFloat));
+            assert_eq!(input.to_elixir_pretty(), Err(ErrorKind::NonFiniteFloat));
+        }
+        assert_eq!(
+            keylist(vec![("a", Value::Float(f64::NAN))]).to_string(),
+            r#"[a: ":nan"]"#
+        );
+        assert_eq!(Value::Float(f64::NAN).to_string(), ":nan");
+        assert_eq!(
+            Value::List(vec![
+                Value::Float(f64::INFINITY),
+                Value::Float(f64::NEG_INFINITY)
+            ])
+            .to_string(),
+            "[:infinity, :neg_infinity]"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("[a: 1"), (1, 6, ErrorKind::UnexpectedEof));
+        assert_eq!(
+            error("[a: 1,\n b: foo]"),
+            (2, 5, ErrorKind::UnknownIdentifier("foo".to_string()))
+        );
+        assert_eq!(error("[a:1]"), (1, 4, ErrorKind::MissingSpaceAfterKeyword));
+        assert_eq!(error("[a: 1, 2]"), (1, 8, ErrorKind::KeywordNotLast));
+        assert_eq!(error("[1, a: 2]"), (1, 2, ErrorKind::ExpectedKeyword));
+        assert_eq!(error("[a: 1_]"), (1, 6, ErrorKind::InvalidNumber));
+        assert_eq!(
+            error("[a: 99999999999999999999]"),
+            (1, 5, ErrorKind::IntegerOverflow)
+        );
+        assert_eq!(error(r#"[a: "x#{y}"]"#), (1, 7, ErrorKind::Interpolation));
+        assert_eq!(
+            error(r#"[é: "\q\u{110000}"]"#),
+            (1, 2, ErrorKind::UnexpectedChar('é'))
+        );
+        assert_eq!(
+            error(r#"[a: "\u{110000}"]"#),
+            (1, 6, ErrorKind::InvalidEscape)
+        );
+        assert_eq!(error("[a: 1] x"), (1, 8, ErrorKind::TrailingCharacters));
+        assert_eq!(error("{:a, 1}"), (1, 1, ErrorKind::ExpectedList));
+        assert_eq!(
+            error("[a: 1; b: 2]"),
+            (1, 6, ErrorKind::UnexpectedChar(';'))
+        );
+    }
+
+    #[test]
+    fn too_deep() {
+        let nested =
+            |depth: usize| format!("[a: {}{}]", "[{".repeat(depth / 2), "}]".repeat(depth / 2));
+
+        assert!(parse(&nested(128)).is_ok());
+        assert_eq!(error(&nested(130)), (1, 133, ErrorKind::TooDeep));
+        assert_eq!(error(&nested(100_000)).2, ErrorKind::TooDeep);
+    }
+
+    #[test]
+    fn error_display() {
+        let err = parse("[a: 1,\n  b: ]").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 6: unexpected character ']'"
+        );
+    }
+}
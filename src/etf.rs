@@ -0,0 +1,689 @@
+//! Erlang External Term Format codec for keyword lists.
+//!
+//! Encodes a `VecKeylist<Atom, Term>` the same way `:erlang.term_to_binary/1` encodes a keyword list,
+//! so the bytes can be exchanged with a BEAM node.
+//! ```
+//! use keylist::etf::{Atom, Term};
+//! use keylist::VecKeylist;
+//!
+//! let mut keylist = VecKeylist::new();
+//! keylist.push(Atom::from("retries"), Term::Integer(3));
+//! keylist.push(Atom::from("name"), Term::Binary(b"svc".to_vec()));
+//!
+//! let bytes = keylist.to_etf().unwrap();
+//! assert_eq!(&bytes[..2], &[131, 108]);
+//!
+//! assert_eq!(VecKeylist::from_etf(&bytes), Ok(keylist));
+//! ```
+
+use crate::VecKeylist;
+use std::convert::TryFrom;
+use std::fmt;
+
+const VERSION: u8 = 131;
+
+const NEW_FLOAT_EXT: u8 = 70;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const FLOAT_EXT: u8 = 99;
+const ATOM_EXT: u8 = 100;
+const SMALL_TUPLE_EXT: u8 = 104;
+const LARGE_TUPLE_EXT: u8 = 105;
+const NIL_EXT: u8 = 106;
+const STRING_EXT: u8 = 107;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const LARGE_BIG_EXT: u8 = 111;
+const SMALL_ATOM_EXT: u8 = 115;
+const ATOM_UTF8_EXT: u8 = 118;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+
+/// The BEAM limits atoms to 255 characters, which can take up to 1020 bytes in UTF-8.
+const MAX_ATOM_CHARS: usize = 255;
+
+/// How deep lists and tuples can be nested when decoding, deeper input is rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Atom(String);
+
+impl Atom {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Atom(name.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(name: &str) -> Self {
+        Atom(name.to_string())
+    }
+}
+
+impl From<String> for Atom {
+    fn from(name: String) -> Self {
+        Atom(name)
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The subset of Erlang terms that can be stored as a keyword list value.
+///
+/// Booleans and `nil` are atoms on the BEAM, so they are represented as `Term::Atom`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Atom(Atom),
+    Integer(i64),
+    Float(f64),
+    Binary(Vec<u8>),
+    Tuple(Vec<Term>),
+    List(Vec<Term>),
+}
+
+impl From<VecKeylist<Atom, Term>> for Term {
+    fn from(keylist: VecKeylist<Atom, Term>) -> Self {
+        Term::List(
+            keylist
+                .into_iter()
+                .map(|(k, v)| Term::Tuple(vec![Term::Atom(k), v]))
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// the input ended in the middle of a term
+    UnexpectedEof,
+    /// the input does not start with the version byte 131
+    InvalidVersion(u8),
+    /// the term uses a tag that is not supported, for example compressed terms, pids or maps
+    UnsupportedTag(u8),
+    InvalidUtf8,
+    /// an integer does not fit in an `i64`
+    IntegerOverflow,
+    /// the float can not be parsed, or is NaN or infinite, which the format can not represent
+    InvalidFloat,
+    ImproperList,
+    /// the decoded term is not a list of `{atom, term}` tuples
+    NotAKeylist,
+    /// there were bytes left after decoding the term
+    TrailingBytes(usize),
+    /// atoms are limited to 255 characters
+    AtomTooLong(usize),
+    /// a list, tuple or binary is too large for the format
+    TooLarge(usize),
+    /// lists and tuples are nested deeper than 128 levels
+    TooDeep,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnexpectedEof => f.write_str("unexpected end of input"),
+            Error::InvalidVersion(v) => write!(f, "invalid version byte {}, expected 131", v),
+            Error::UnsupportedTag(tag) => write!(f, "unsupported term tag {}", tag),
+            Error::InvalidUtf8 => f.write_str("atom is not valid utf-8"),
+            Error::IntegerOverflow => f.write_str("integer does not fit in an i64"),
+            Error::InvalidFloat => f.write_str("invalid float"),
+            Error::ImproperList => f.write_str("improper lists are not supported"),
+            Error::NotAKeylist => f.write_str("term is not a list of {atom, term} tuples"),
+            Error::TrailingBytes(n) => write!(f, "{} trailing bytes after term", n),
+            Error::AtomTooLong(n) => write!(f, "atom of {} characters is too long", n),
+            Error::TooLarge(n) => write!(f, "term with {} elements is too large", n),
+            Error::TooDeep => f.write_str("terms are nested too deep"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl VecKeylist<Atom, Term> {
+    /// Encode the keylist as a list of 2-tuples, like `:erlang.term_to_binary/1` does.
+    pub fn to_etf(&self) -> Result<Vec<u8>, Error> {
+        let mut buffer = vec![VERSION];
+        if self.is_empty() {
+            buffer.push(NIL_EXT);
+            return Ok(buffer);
+        }
+        buffer.push(LIST_EXT);
+        write_length(&mut buffer, self.len())?;
+        for (k, v) in self.iter() {
+            buffer.extend_from_slice(&[SMALL_TUPLE_EXT, 2]);
+            write_atom(&mut buffer, k)?;
+            write_term(&mut buffer, v)?;
+        }
+        buffer.push(NIL_EXT);
+        Ok(buffer)
+    }
+
+    /// Decode a keyword list, as produced by `:erlang.term_to_binary/1`.
+    pub fn from_etf(bytes: &[u8]) -> Result<Self, Error> {
+        match decode(bytes)? {
+            Term::List(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    Term::Tuple(mut pair) if pair.len() == 2 => {
+                        let value = pair.pop().unwrap();
+                        match pair.pop().unwrap() {
+                            Term::Atom(key) => Ok((key, value)),
+                            _ => Err(Error::NotAKeylist),
+                        }
+                    }
+                    _ => Err(Error::NotAKeylist),
+                })
+                .collect(),
+            _ => Err(Error::NotAKeylist),
+        }
+    }
+}
+
+/// Encode a single term, including the version byte.
+pub fn encode(term: &Term) -> Result<Vec<u8>, Error> {
+    let mut buffer = vec![VERSION];
+    write_term(&mut buffer, term)?;
+    Ok(buffer)
+}
+
+/// Decode a single term, the input should start with the version byte.
+pub fn decode(bytes: &[u8]) -> Result<Term, Error> {
+    let mut reader = Reader { bytes };
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(Error::InvalidVersion(version));
+    }
+    let term = reader.term(0)?;
+    if !reader.bytes.is_empty() {
+        return Err(Error::TrailingBytes(reader.bytes.len()));
+    }
+    Ok(term)
+}
+
+fn write_length(buffer: &mut Vec<u8>, len: usize) -> Result<(), Error> {
+    let len = u32::try_from(len).map_err(|_| Error::TooLarge(len))?;
+    buffer.extend_from_slice(&len.to_be_bytes());
+    Ok(())
+}
+
+fn write_atom(buffer: &mut Vec<u8>, atom: &Atom) -> Result<(), Error> {
+    let chars = atom.as_str().chars().count();
+    if chars > MAX_ATOM_CHARS {
+        return Err(Error::AtomTooLong(chars));
+    }
+    let name = atom.as_str().as_bytes();
+    if let Ok(len) = u8::try_from(name.len()) {
+        buffer.extend_from_slice(&[SMALL_ATOM_UTF8_EXT, len]);
+    } else {
+        buffer.push(ATOM_UTF8_EXT);
+        buffer.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    }
+    buffer.extend_from_slice(name);
+    Ok(())
+}
+
+fn write_integer(buffer: &mut Vec<u8>, int: i64) {
+    if let Ok(small) = u8::try_from(int) {
+        buffer.extend_from_slice(&[SMALL_INTEGER_EXT, small]);
+    } else if let Ok(int) = i32::try_from(int) {
+        buffer.push(INTEGER_EXT);
+        buffer.extend_from_slice(&int.to_be_bytes());
+    } else {
+        let sign = (int < 0) as u8;
+        let digits = int.unsigned_abs().to_le_bytes();
+        let n = digits.iter().rposition(|&d| d != 0).unwrap() + 1;
+        buffer.extend_from_slice(&[SMALL_BIG_EXT, n as u8, sign]);
+        buffer.extend_from_slice(&digits[..n]);
+    }
+}
+
+/// Erlang encodes lists of bytes as `STRING_EXT`
+fn as_byte_string(items: &[Term]) -> Option<Vec<u8>> {
+    if items.is_empty() || items.len() > u16::MAX as usize {
+        return None;
+    }
+    items
+        .iter()
+        .map(|item| match item {
+            Term::Integer(int) => u8::try_from(*int).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn write_term(buffer: &mut Vec<u8>, term: &Term) -> Result<(), Error> {
+    match term {
+        Term::Atom(atom) => write_atom(buffer, atom)?,
+        Term::Integer(int) => write_integer(buffer, *int),
+        Term::Float(float) if !float.is_finite() => return Err(Error::InvalidFloat),
+        Term::Float(float) => {
+            buffer.push(NEW_FLOAT_EXT);
+            buffer.extend_from_slice(&float.to_bits().to_be_bytes());
+        }
+        Term::Binary(data) => {
+            buffer.push(BINARY_EXT);
+            write_length(buffer, data.len())?;
+            buffer.extend_from_slice(data);
+        }
+        Term::Tuple(items) => {
+            if let Ok(arity) = u8::try_from(items.len()) {
+                buffer.extend_from_slice(&[SMALL_TUPLE_EXT, arity]);
+            } else {
+                buffer.push(LARGE_TUPLE_EXT);
+                write_length(buffer, items.len())?;
+            }
+            for item in items {
+                write_term(buffer, item)?;
+            }
+        }
+        Term::List(items) if items.is_empty() => buffer.push(NIL_EXT),
+        Term::List(items) => {
+            if let Some(string) = as_byte_string(items) {
+                buffer.push(STRING_EXT);
+                buffer.extend_from_slice(&(string.len() as u16).to_be_bytes());
+                buffer.extend_from_slice(&string);
+            } else {
+                buffer.push(LIST_EXT);
+                write_length(buffer, items.len())?;
+                for item in items {
+                    write_term(buffer, item)?;
+                }
+                buffer.push(NIL_EXT);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Erlang has no NaN or infinity, so neither does the format.
+fn finite_float(float: f64) -> Result<Term, Error> {
+    if float.is_finite() {
+        Ok(Term::Float(float))
+    } else {
+        Err(Error::InvalidFloat)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < n {
+            return Err(Error::UnexpectedEof);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<usize, Error> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+    }
+
+    fn u32(&mut self) -> Result<usize, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn latin1_atom(&mut self, len: usize) -> Result<Term, Error> {
+        if len > MAX_ATOM_CHARS {
+            return Err(Error::AtomTooLong(len));
+        }
+        let name = self.take(len)?.iter().map(|&b| b as char).collect();
+        Ok(Term::Atom(Atom(name)))
+    }
+
+    fn utf8_atom(&mut self, len: usize) -> Result<Term, Error> {
+        let name = std::str::from_utf8(self.take(len)?).map_err(|_| Error::InvalidUtf8)?;
+        let chars = name.chars().count();
+        if chars > MAX_ATOM_CHARS {
+            return Err(Error::AtomTooLong(chars));
+        }
+        Ok(Term::Atom(Atom::from(name)))
+    }
+
+    fn big(&mut self, n: usize) -> Result<Term, Error> {
+        let sign = self.u8()?;
+        let digits = self.take(n)?;
+        let mut magnitude: u64 = 0;
+        for (i, &digit) in digits.iter().enumerate() {
+            if digit == 0 {
+                continue;
+            }
+            if i >= 8 {
+                return Err(Error::IntegerOverflow);
+            }
+            magnitude |= (digit as u64) << (8 * i);
+        }
+        let int = if sign == 0 {
+            i64::try_from(magnitude).map_err(|_| Error::IntegerOverflow)?
+        } else if magnitude == i64::MIN.unsigned_abs() {
+            i64::MIN
+        } else {
+            -i64::try_from(magnitude).map_err(|_| Error::IntegerOverflow)?
+        };
+        Ok(Term::Integer(int))
+    }
+
+    fn terms(&mut self, n: usize, depth: usize) -> Result<Vec<Term>, Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        // do not trust the length for the allocation, every term is at least one byte
+        let mut items = Vec::with_capacity(n.min(self.bytes.len()));
+        for _ in 0..n {
+            items.push(self.term(depth)?);
+        }
+        Ok(items)
+    }
+
+    fn term(&mut self, depth: usize) -> Result<Term, Error> {
+        let term = match self.u8()? {
+            SMALL_INTEGER_EXT => Term::Integer(self.u8()? as i64),
+            INTEGER_EXT => {
+                let bytes = self.take(4)?;
+                Term::Integer(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64)
+            }
+            SMALL_BIG_EXT => {
+                let n = self.u8()? as usize;
+                self.big(n)?
+            }
+            LARGE_BIG_EXT => {
+                let n = self.u32()?;
+                self.big(n)?
+            }
+            NEW_FLOAT_EXT => {
+                let mut bits = [0; 8];
+                bits.copy_from_slice(self.take(8)?);
+                finite_float(f64::from_bits(u64::from_be_bytes(bits)))?
+            }
+            FLOAT_EXT => {
+                let text = std::str::from_utf8(self.take(31)?).map_err(|_| Error::InvalidFloat)?;
+                let float = text
+                    .trim_end_matches('\0')
+                    .trim()
+                    .parse()
+                    .map_err(|_| Error::InvalidFloat)?;
+                finite_float(float)?
+            }
+            ATOM_EXT => {
+                let len = self.u16()?;
+                self.latin1_atom(len)?
+            }
+            SMALL_ATOM_EXT => {
+                let len = self.u8()? as usize;
+                self.latin1_atom(len)?
+            }
+            ATOM_UTF8_EXT => {
+                let len = self.u16()?;
+                self.utf8_atom(len)?
+            }
+            SMALL_ATOM_UTF8_EXT => {
+                let len = self.u8()? as usize;
+                self.utf8_atom(len)?
+            }
+            BINARY_EXT => {
+                let len = self.u32()?;
+                Term::Binary(self.take(len)?.to_vec())
+            }
+            SMALL_TUPLE_EXT => {
+                let arity = self.u8()? as usize;
+                Term::Tuple(self.terms(arity, depth + 1)?)
+            }
+            LARGE_TUPLE_EXT => {
+                let arity = self.u32()?;
+                Term::Tuple(self.terms(arity, depth + 1)?)
+            }
+            NIL_EXT => Term::List(Vec::new()),
+            STRING_EXT => {
+                let len = self.u16()?;
+                let bytes = self.take(len)?;
+                Term::List(bytes.iter().map(|&b| Term::Integer(b as i64)).collect())
+            }
+            LIST_EXT => {
+                let len = self.u32()?;
+                let items = self.terms(len, depth + 1)?;
+                if self.u8()? != NIL_EXT {
+                    return Err(Error::ImproperList);
+                }
+                Term::List(items)
+            }
+            tag => return Err(Error::UnsupportedTag(tag)),
+        };
+        Ok(term)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, Atom, Error, Term};
+    use crate::VecKeylist;
+
+    fn atom(name: &str) -> Atom {
+        Atom::from(name)
+    }
+
+    fn binary(data: &str) -> Term {
+        Term::Binary(data.as_bytes().to_vec())
+    }
+
+    // :erlang.term_to_binary([timeout: 5_000, retries: 3, name: "svc"])
+    const SERVICE: &[u8] = &[
+        131, 108, 0, 0, 0, 3, 104, 2, 119, 7, 116, 105, 109, 101, 111, 117, 116, 98, 0, 0, 19, 136,
+        104, 2, 119, 7, 114, 101, 116, 114, 105, 101, 115, 97, 3, 104, 2, 119, 4, 110, 97, 109,
+        101, 109, 0, 0, 0, 3, 115, 118, 99, 106,
+    ];
+
+    fn service() -> VecKeylist<Atom, Term> {
+        VecKeylist(vec![
+            (atom("timeout"), Term::Integer(5000)),
+            (atom("retries"), Term::Integer(3)),
+            (atom("name"), binary("svc")),
+        ])
+    }
+
+    // :erlang.term_to_binary([ratio: 1.5, offset: -1, big: 1 <<< 40, min: -(1 <<< 40)])
+    const NUMBERS: &[u8] = &[
+        131, 108, 0, 0, 0, 4, 104, 2, 119, 5, 114, 97, 116, 105, 111, 70, 63, 248, 0, 0, 0, 0, 0,
+        0, 104, 2, 119, 6, 111, 102, 102, 115, 101, 116, 98, 255, 255, 255, 255, 104, 2, 119, 3,
+        98, 105, 103, 110, 6, 0, 0, 0, 0, 0, 0, 1, 104, 2, 119, 3, 109, 105, 110, 110, 6, 1, 0, 0,
+        0, 0, 0, 1, 106,
+    ];
+
+    fn numbers() -> VecKeylist<Atom, Term> {
+        VecKeylist(vec![
+            (atom("ratio"), Term::Float(1.5)),
+            (atom("offset"), Term::Integer(-1)),
+            (atom("big"), Term::Integer(1 << 40)),
+            (atom("min"), Term::Integer(-(1 << 40))),
+        ])
+    }
+
+    // :erlang.term_to_binary([opts: [debug: true], ports: [1, 2, 3], pair: {:ok, nil}, empty: []])
+    const NESTED: &[u8] = &[
+        131, 108, 0, 0, 0, 4, 104, 2, 119, 4, 111, 112, 116, 115, 108, 0, 0, 0, 1, 104, 2, 119, 5,
+        100, 101, 98, 117, 103, 119, 4, 116, 114, 117, 101, 106, 104, 2, 119, 5, 112, 111, 114,
+        116, 115, 107, 0, 3, 1, 2, 3, 104, 2, 119, 4, 112, 97, 105, 114, 104, 2, 119, 2, 111, 107,
+        119, 3, 110, 105, 108, 104, 2, 119, 5, 101, 109, 112, 116, 121, 106, 106,
+    ];
+
+    fn nested() -> VecKeylist<Atom, Term> {
+        let opts = VecKeylist(vec![(atom("debug"), Term::Atom(atom("true")))]);
+        VecKeylist(vec![
+            (atom("opts"), Term::from(opts)),
+            (
+                atom("ports"),
+                Term::List(vec![Term::Integer(1), Term::Integer(2), Term::Integer(3)]),
+            ),
+            (
+                atom("pair"),
+                Term::Tuple(vec![Term::Atom(atom("ok")), Term::Atom(atom("nil"))]),
+            ),
+            (atom("empty"), Term::List(vec![])),
+        ])
+    }
+
+    #[test]
+    fn encode_service() {
+        assert_eq!(service().to_etf().unwrap(), SERVICE);
+    }
+
+    #[test]
+    fn decode_service() {
+        assert_eq!(VecKeylist::from_etf(SERVICE), Ok(service()));
+    }
+
+    #[test]
+    fn round_trip_numbers() {
+        assert_eq!(numbers().to_etf().unwrap(), NUMBERS);
+        assert_eq!(VecKeylist::from_etf(NUMBERS), Ok(numbers()));
+    }
+
+    #[test]
+    fn round_trip_nested() {
+        assert_eq!(nested().to_etf().unwrap(), NESTED);
+        assert_eq!(VecKeylist::from_etf(NESTED), Ok(nested()));
+    }
+
+    #[test]
+    fn empty() {
+        let keylist = VecKeylist::new();
+
+        assert_eq!(keylist.to_etf().unwrap(), vec![131, 106]);
+        assert_eq!(VecKeylist::from_etf(&[131, 106]), Ok(keylist));
+    }
+
+    #[test]
+    fn decode_legacy_atoms() {
+        // :erlang.term_to_binary([a: 1], minor_version: 1) on OTP 25 and older
+        let bytes = [131, 108, 0, 0, 0, 1, 104, 2, 100, 0, 1, 97, 97, 1, 106];
+        let expected = VecKeylist(vec![(atom("a"), Term::Integer(1))]);
+
+        assert_eq!(VecKeylist::from_etf(&bytes), Ok(expected));
+    }
+
+    #[test]
+    fn long_atom() {
+        let name = "a".repeat(255);
+        let bytes = encode(&Term::Atom(atom(&name))).unwrap();
+        assert_eq!(&bytes[..3], &[131, 119, 255]);
+        assert_eq!(decode(&bytes), Ok(Term::Atom(atom(&name))));
+
+        // the limit is in characters, so a UTF-8 atom can be longer than 255 bytes
+        let name = "é".repeat(255);
+        let bytes = encode(&Term::Atom(atom(&name))).unwrap();
+        assert_eq!(&bytes[..4], &[131, 118, 1, 254]);
+        assert_eq!(decode(&bytes), Ok(Term::Atom(atom(&name))));
+
+        assert_eq!(
+            encode(&Term::Atom(atom(&"a".repeat(256)))),
+            Err(Error::AtomTooLong(256))
+        );
+        assert_eq!(
+            encode(&Term::Atom(atom(&"é".repeat(256)))),
+            Err(Error::AtomTooLong(256))
+        );
+
+        let mut bytes = vec![131, 118, 1, 0];
+        bytes.extend_from_slice("a".repeat(256).as_bytes());
+        assert_eq!(decode(&bytes), Err(Error::AtomTooLong(256)));
+        let mut bytes = vec![131, 100, 1, 0];
+        bytes.extend_from_slice("a".repeat(256).as_bytes());
+        assert_eq!(decode(&bytes), Err(Error::AtomTooLong(256)));
+    }
+
+    #[test]
+    fn integer_limits() {
+        for int in &[
+            0,
+            255,
+            256,
+            -1,
+            i32::MAX as i64,
+            i32::MIN as i64,
+            i64::MAX,
+            i64::MIN,
+        ] {
+            let bytes = encode(&Term::Integer(*int)).unwrap();
+            assert_eq!(decode(&bytes), Ok(Term::Integer(*int)));
+        }
+    }
+
+    #[test]
+    fn integer_overflow() {
+        // :erlang.term_to_binary(1 <<< 64)
+        let bytes = [131, 110, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+
+        assert_eq!(decode(&bytes), Err(Error::IntegerOverflow));
+    }
+
+    #[test]
+    fn not_a_keylist() {
+        // :erlang.term_to_binary([1])
+        assert_eq!(
+            VecKeylist::from_etf(&[131, 107, 0, 1, 1]),
+            Err(Error::NotAKeylist)
+        );
+        // :erlang.term_to_binary([{"a", 1}])
+        assert_eq!(
+            VecKeylist::from_etf(&[131, 108, 0, 0, 0, 1, 104, 2, 107, 0, 1, 97, 97, 1, 106]),
+            Err(Error::NotAKeylist)
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(decode(&[]), Err(Error::UnexpectedEof));
+        assert_eq!(decode(&[130, 106]), Err(Error::InvalidVersion(130)));
+        assert_eq!(decode(&[131, 80, 0]), Err(Error::UnsupportedTag(80)));
+        assert_eq!(decode(&[131, 106, 106]), Err(Error::TrailingBytes(1)));
+        assert_eq!(
+            decode(&[131, 108, 0, 0, 0, 1, 97, 1, 97, 2]),
+            Err(Error::ImproperList)
+        );
+        assert_eq!(
+            decode(&[131, 108, 255, 255, 255, 255, 97, 1]),
+            Err(Error::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn non_finite_float() {
+        for float in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(encode(&Term::Float(*float)), Err(Error::InvalidFloat));
+
+            let mut bytes = vec![131, 70];
+            bytes.extend_from_slice(&float.to_bits().to_be_bytes());
+            assert_eq!(decode(&bytes), Err(Error::InvalidFloat));
+        }
+    }
+
+    #[test]
+    fn too_deep() {
+        let nested = |depth: usize| {
+            let mut bytes = vec![131];
+            bytes.extend([104, 1].repeat(depth));
+            bytes.push(106);
+            bytes
+        };
+
+        assert!(decode(&nested(128)).is_ok());
+        assert_eq!(decode(&nested(129)), Err(Error::TooDeep));
+        assert_eq!(decode(&nested(100_000)), Err(Error::TooDeep));
+    }
+}
@@ -1,20 +1,41 @@
+use crate::VecKeylist;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FromIterator;
+#[cfg(feature = "std")]
 use std::collections::hash_map::RandomState;
-use std::collections::HashMap;
-use std::hash::{BuildHasher, Hash};
-use std::iter::FromIterator;
+
+/// The std map, or the `hashbrown` map without the `std` feature.
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::HashMap;
+#[cfg(feature = "std")]
+pub(crate) use std::collections::HashMap;
 
 pub mod iter;
 use iter::*;
 
-#[derive(Debug)]
 pub struct HashKeylist<K, V, S>
 where
     K: Hash + Eq,
     V: Eq,
     S: BuildHasher,
 {
-    data: HashMap<K, Vec<V>, S>,
-    keys: Vec<K>,
+    pub(crate) data: HashMap<K, Vec<V>, S>,
+    pub(crate) keys: Vec<K>,
+}
+
+/// Formats as a list of pairs in insertion order: `[("a", 1), ("b", 2)]`.
+impl<K, V, S> fmt::Debug for HashKeylist<K, V, S>
+where
+    K: fmt::Debug + Hash + Eq,
+    V: fmt::Debug + Eq,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
 }
 
 // fn make_hash<K: Hash + ?Sized>(hash_builder: &impl BuildHasher, val: &K) -> u64 {
@@ -23,6 +44,7 @@ where
 //     state.finish()
 // }
 
+/// Compares the ordered pairs, not the internal layout.
 impl<K, V, S> PartialEq for HashKeylist<K, V, S>
 where
     K: Hash + Eq,
@@ -30,10 +52,133 @@ where
     S: BuildHasher,
 {
     fn eq(&self, other: &Self) -> bool {
-        (self.data == other.data) & (self.keys == other.keys)
+        self.keys == other.keys && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V, S> Eq for HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K, V, S> PartialOrd for HashKeylist<K, V, S>
+where
+    K: Hash + Eq + PartialOrd,
+    V: Eq + PartialOrd,
+    S: BuildHasher,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K, V, S> Ord for HashKeylist<K, V, S>
+where
+    K: Hash + Ord,
+    V: Ord,
+    S: BuildHasher,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+/// Hashes the same as a `VecKeylist` or `Vec` with the same pairs.
+impl<K, V, S> Hash for HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for pair in self.iter() {
+            pair.hash(state);
+        }
+    }
+}
+
+impl<K, V, S> Default for HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher + Default,
+{
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> PartialEq<VecKeylist<K, V>> for HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        *self == other.0[..]
+    }
+}
+
+impl<K, V, S> PartialEq<HashKeylist<K, V, S>> for VecKeylist<K, V>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &HashKeylist<K, V, S>) -> bool {
+        *other == self.0[..]
+    }
+}
+
+impl<K, V, S> PartialEq<[(K, V)]> for HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &[(K, V)]) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl<K, V, S> PartialEq<Vec<(K, V)>> for HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Vec<(K, V)>) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<K, V, S> PartialEq<HashKeylist<K, V, S>> for [(K, V)]
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &HashKeylist<K, V, S>) -> bool {
+        *other == *self
     }
 }
 
+impl<K, V, S> PartialEq<HashKeylist<K, V, S>> for Vec<(K, V)>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &HashKeylist<K, V, S>) -> bool {
+        *other == self[..]
+    }
+}
+
+#[cfg(feature = "std")]
 impl<K, V> HashKeylist<K, V, RandomState>
 where
     K: Hash + Eq,
@@ -74,7 +219,7 @@ where
         }
     }
 
-    pub fn iter(&self) -> Iter<K, V> {
+    pub fn iter(&self) -> Iter<'_, K, V> {
         let map = HashMap::from_iter(
             self.data
                 .iter()
@@ -82,11 +227,11 @@ where
         );
         Iter {
             keys: self.keys.iter(),
-            map: map,
+            map,
         }
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<K, V> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
         let map = HashMap::from_iter(self.data.iter_mut().map(|(k, vs)| {
             (
                 k,
@@ -97,19 +242,19 @@ where
         }));
         IterMut {
             keys: self.keys.iter(),
-            map: map,
+            map,
         }
     }
 
-    pub fn keys<'a>(&'a self) -> impl Iterator<Item = &'a K> {
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
         self.keys.iter()
     }
 
-    pub fn values<'a>(&'a self) -> impl Iterator<Item = &'a V> {
+    pub fn values(&self) -> impl Iterator<Item = &V> {
         self.iter().map(|(_, v)| v)
     }
 
-    pub fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut V> {
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
         self.iter_mut().map(|(_, v)| v)
     }
 
@@ -133,6 +278,13 @@ where
         (key, value)
     }
 
+    /// Removes all the values of the key, returns `None` if the key is not in the list.
+    pub fn remove_all(&mut self, key: &K) -> Option<Vec<V>> {
+        let values = self.data.remove(key)?;
+        self.keys.retain(|k| k != key);
+        Some(values)
+    }
+
     fn pop_and_clean(&mut self, key: &K) -> Option<V> {
         let list = self.get_all_mut(key)?;
         let value = list.pop();
@@ -200,13 +352,13 @@ where
 {
     pub fn insert(&mut self, index: usize, key: K, value: V) {
         let pos = self.index_to_position(&key, index);
-        let entry = self.data.entry(key.clone()).or_insert(Vec::new());
+        let entry = self.data.entry(key.clone()).or_default();
         entry.insert(pos, value);
         self.keys.insert(index, key);
     }
 
     pub fn push(&mut self, k: K, v: V) {
-        let entry = self.data.entry(k.clone()).or_insert(Vec::new());
+        let entry = self.data.entry(k.clone()).or_default();
         entry.push(v);
         self.keys.push(k)
     }
@@ -214,22 +366,22 @@ where
 
 impl<K, V, S> HashKeylist<K, V, S>
 where
-    K: Hash + Eq + Clone + std::cmp::Ord,
+    K: Hash + Eq + Clone + core::cmp::Ord,
     V: Eq,
     S: BuildHasher,
 {
-    pub fn sort_by_key<'a>(&'a mut self) {
+    pub fn sort_by_key(&mut self) {
         self.keys.sort_unstable()
     }
 }
 
 impl<K, V, S> HashKeylist<K, V, S>
 where
-    K: Hash + Eq + Clone + std::cmp::Ord,
-    V: Eq + std::cmp::Ord,
+    K: Hash + Eq + Clone + core::cmp::Ord,
+    V: Eq + core::cmp::Ord,
     S: BuildHasher,
 {
-    pub fn sort<'a>(&'a mut self) {
+    pub fn sort(&mut self) {
         self.sort_by_key();
         for item in self.data.values_mut() {
             item.sort();
@@ -237,6 +389,66 @@ where
     }
 }
 
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    /// first pair with the value
+    ///
+    /// There is no index on values, this walks the pairs in order and is O(n).
+    pub fn find_by_value(&self, value: &V) -> Option<(&K, &V)> {
+        self.pairs_with_value(value).next()
+    }
+
+    /// get all keys that have the value, in order
+    ///
+    /// There is no index on values, this walks the pairs in order and is O(n).
+    pub fn keys_for_value(&self, value: &V) -> Vec<&K> {
+        self.pairs_with_value(value).map(|(k, _)| k).collect()
+    }
+
+    /// Like `iter`, but only keeps cursors for the keys that hold the value somewhere.
+    fn pairs_with_value<'a: 'b, 'b>(
+        &'a self,
+        value: &'b V,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + 'b {
+        let mut rows: HashMap<&K, RowIter<V>> = self
+            .data
+            .iter()
+            .filter(|(_, vs)| vs.contains(value))
+            .map(|(k, vs)| (k, RowIter { values: vs.iter() }))
+            .collect();
+        self.keys.iter().filter_map(move |key| {
+            let v = rows.get_mut(key)?.next()?;
+            if v == value {
+                Some((key, v))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Hash + Eq + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Swaps keys and values, keeping the order of the pairs and the hasher.
+    pub fn into_swapped(self) -> HashKeylist<V, K, S> {
+        let hasher = self.data.hasher().clone();
+        let mut swapped = HashKeylist::with_capacity_and_hasher(self.len(), hasher);
+        for (k, v) in self {
+            swapped.push(v, k);
+        }
+        swapped
+    }
+}
+
+#[cfg(feature = "std")]
 impl<K, V> From<Vec<(K, V)>> for HashKeylist<K, V, RandomState>
 where
     K: Hash + Eq + Clone,
@@ -255,17 +467,30 @@ where
     }
 }
 
-impl<K, V> From<HashKeylist<K, V, RandomState>> for Vec<(K, V)>
+#[cfg(feature = "std")]
+impl<K, V, const N: usize> From<[(K, V); N]> for HashKeylist<K, V, RandomState>
 where
     K: Hash + Eq + Clone,
     V: Eq,
 {
-    fn from(input: HashKeylist<K, V, RandomState>) -> Vec<(K, V)> {
+    fn from(input: [(K, V); N]) -> Self {
+        IntoIterator::into_iter(input).collect()
+    }
+}
+
+impl<K, V, S> From<HashKeylist<K, V, S>> for Vec<(K, V)>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn from(input: HashKeylist<K, V, S>) -> Vec<(K, V)> {
         input.into_iter().collect()
     }
 }
 
-impl<K, V> std::iter::FromIterator<(K, V)> for HashKeylist<K, V, RandomState>
+#[cfg(feature = "std")]
+impl<K, V> FromIterator<(K, V)> for HashKeylist<K, V, RandomState>
 where
     K: Hash + Eq + Clone,
     V: Eq,
@@ -285,6 +510,47 @@ where
     }
 }
 
+/// Without `std` there is no `RandomState`, so any hasher with a `Default` works.
+#[cfg(not(feature = "std"))]
+impl<K, V, S> From<Vec<(K, V)>> for HashKeylist<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Eq,
+    S: BuildHasher + Default,
+{
+    fn from(input: Vec<(K, V)>) -> Self {
+        input.into_iter().collect()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<K, V, S, const N: usize> From<[(K, V); N]> for HashKeylist<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Eq,
+    S: BuildHasher + Default,
+{
+    fn from(input: [(K, V); N]) -> Self {
+        IntoIterator::into_iter(input).collect()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<K, V, S> FromIterator<(K, V)> for HashKeylist<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Eq,
+    S: BuildHasher + Default,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let iter = iter.into_iter();
+        let (size, _) = iter.size_hint();
+        let mut keylist = HashKeylist::with_capacity_and_hasher(size, S::default());
+        keylist.extend(iter);
+        keylist
+    }
+}
+
 impl<K, V, S> Extend<(K, V)> for HashKeylist<K, V, S>
 where
     K: Eq + Hash + Clone,
@@ -294,7 +560,7 @@ where
     #[inline]
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
         for (k, v) in iter {
-            let entry = self.data.entry(k.clone()).or_insert(Vec::new());
+            let entry = self.data.entry(k.clone()).or_default();
             entry.push(v);
             self.keys.push(k);
         }
@@ -318,11 +584,11 @@ where
 #[cfg(feature = "serde")]
 mod serde {
     use crate::HashKeylist;
+    use alloc::vec::Vec;
+    use core::hash::{BuildHasher, Hash};
+    use core::marker::PhantomData;
     use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
     use serde::ser::{Serialize, Serializer};
-    use std::collections::hash_map::RandomState;
-    use std::hash::{BuildHasher, Hash};
-    use std::marker::PhantomData;
 
     impl<K, V, H> Serialize for HashKeylist<K, V, H>
     where
@@ -338,6 +604,7 @@ mod serde {
         }
     }
 
+    #[allow(clippy::type_complexity)]
     struct KeylistVisitor<K, V, H>
     where
         K: Hash + Eq,
@@ -360,13 +627,14 @@ mod serde {
         }
     }
 
-    impl<'de, K, V> Visitor<'de> for KeylistVisitor<K, V, RandomState>
+    impl<'de, K, V, H> Visitor<'de> for KeylistVisitor<K, V, H>
     where
         K: Deserialize<'de> + Hash + Eq + Clone,
         V: Deserialize<'de> + Eq,
+        H: BuildHasher + Default,
     {
-        type Value = HashKeylist<K, V, RandomState>;
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        type Value = HashKeylist<K, V, H>;
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
             formatter.write_str("Struct VecKeylist")
         }
 
@@ -374,31 +642,28 @@ mod serde {
         where
             X: SeqAccess<'de>,
         {
-            let mut buffer = Vec::new();
+            let mut keylist = HashKeylist::with_hasher(H::default());
 
-            while let Some(x) = seq.next_element()? {
-                buffer.push(x)
+            while let Some((k, v)) = seq.next_element()? {
+                keylist.push(k, v)
             }
 
-            Ok(HashKeylist::from(buffer))
+            Ok(keylist)
         }
 
         fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
         where
             M: MapAccess<'de>,
         {
-            let mut buffer = Vec::new();
+            let mut keylist = HashKeylist::with_hasher(H::default());
 
-            while let Some(x) = access.next_entry()? {
-                buffer.push(x)
+            while let Some((k, v)) = access.next_entry()? {
+                keylist.push(k, v)
             }
-            Ok(HashKeylist::from(buffer))
+            Ok(keylist)
         }
 
-        fn visit_newtype_struct<D>(
-            self,
-            deserializer: D,
-        ) -> Result<HashKeylist<K, V, RandomState>, D::Error>
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<HashKeylist<K, V, H>, D::Error>
         where
             D: Deserializer<'de>,
         {
@@ -406,10 +671,27 @@ mod serde {
         }
     }
 
-    impl<'de, K, V> Deserialize<'de> for HashKeylist<K, V, RandomState>
+    #[cfg(feature = "std")]
+    impl<'de, K, V> Deserialize<'de> for HashKeylist<K, V, std::collections::hash_map::RandomState>
+    where
+        K: Deserialize<'de> + Hash + Eq + Clone,
+        V: Deserialize<'de> + Eq,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(KeylistVisitor::new())
+        }
+    }
+
+    /// Without `std` there is no `RandomState`, so any hasher with a `Default` works.
+    #[cfg(not(feature = "std"))]
+    impl<'de, K, V, H> Deserialize<'de> for HashKeylist<K, V, H>
     where
         K: Deserialize<'de> + Hash + Eq + Clone,
         V: Deserialize<'de> + Eq,
+        H: BuildHasher + Default,
     {
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
@@ -420,11 +702,12 @@ mod serde {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    use crate::HashKeylist;
-    use std::collections::hash_map::RandomState;
+    use crate::{HashKeylist, VecKeylist};
+    use std::collections::hash_map::{DefaultHasher, RandomState};
     use std::collections::HashMap;
+    use std::hash::{BuildHasherDefault, Hash, Hasher};
     use std::iter::FromIterator;
 
     fn data() -> HashKeylist<&'static str, u32, RandomState> {
@@ -453,6 +736,106 @@ mod tests {
         assert_eq!(None, iter.next());
     }
 
+    #[test]
+    fn eq_ignores_layout() {
+        let mut keylist = data();
+        keylist.push("other", 5);
+        keylist.pop();
+
+        assert_eq!(keylist, data());
+
+        let mut other_hasher: HashKeylist<_, _, BuildHasherDefault<DefaultHasher>> =
+            HashKeylist::default();
+        other_hasher.extend(vec![("oke", 1), ("test", 19), ("oke", 2)]);
+        assert!(other_hasher == VecKeylist(vec![("oke", 1), ("test", 19), ("oke", 2)]));
+
+        keylist.remove(0);
+        keylist.push("oke", 1);
+        assert_ne!(keylist, data());
+    }
+
+    #[test]
+    fn eq_other_types() {
+        let keylist = data();
+        let pairs = vec![("oke", 1), ("test", 19), ("oke", 2)];
+
+        assert!(keylist == VecKeylist(pairs.clone()));
+        assert!(VecKeylist(pairs.clone()) == keylist);
+        assert!(keylist == pairs);
+        assert!(pairs == keylist);
+        assert!(keylist == pairs[..]);
+        assert!(pairs[..] == keylist);
+        assert!(keylist != pairs[..2]);
+        assert!(keylist != vec![("oke", 1), ("oke", 2), ("test", 19)]);
+    }
+
+    #[test]
+    fn hash_and_ord() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let hash = |x: &dyn Fn(&mut DefaultHasher)| {
+            let mut hasher = DefaultHasher::new();
+            x(&mut hasher);
+            hasher.finish()
+        };
+        let keylist = data();
+        let pairs = vec![("oke", 1), ("test", 19), ("oke", 2)];
+
+        assert_eq!(
+            hash(&|h| keylist.hash(h)),
+            hash(&|h| VecKeylist(pairs.clone()).hash(h))
+        );
+        assert_eq!(hash(&|h| keylist.hash(h)), hash(&|h| pairs.hash(h)));
+
+        let set: HashSet<_> = vec![data(), data(), HashKeylist::new()]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+
+        let set: BTreeSet<_> = vec![
+            data(),
+            HashKeylist::from(vec![("a", 1)]),
+            HashKeylist::new(),
+        ]
+        .into_iter()
+        .collect();
+        let first: Vec<_> = set.iter().map(|x| x.len()).collect();
+        assert_eq!(first, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn into_swapped() {
+        let keylist = data().into_swapped();
+
+        assert_eq!(keylist, vec![(1, "oke"), (19, "test"), (2, "oke")]);
+        assert_eq!(keylist.get(&19), Some(&"test"));
+    }
+
+    #[test]
+    fn by_value() {
+        let mut keylist = data();
+        keylist.push("other", 1);
+
+        assert_eq!(keylist.find_by_value(&1), Some((&"oke", &1)));
+        assert_eq!(keylist.find_by_value(&3), None);
+        assert_eq!(keylist.keys_for_value(&1), vec![&"oke", &"other"]);
+        assert!(keylist.keys_for_value(&3).is_empty());
+
+        keylist.push("test", 2);
+        assert_eq!(keylist.find_by_value(&2), Some((&"oke", &2)));
+        assert_eq!(keylist.keys_for_value(&2), vec![&"oke", &"test"]);
+    }
+
+    #[test]
+    fn debug() {
+        let keylist = data();
+
+        assert_eq!(
+            format!("{:?}", keylist),
+            r#"[("oke", 1), ("test", 19), ("oke", 2)]"#
+        );
+    }
+
     #[test]
     fn into_iter() {
         let keylist = data();
@@ -602,6 +985,15 @@ mod tests {
         assert_eq!(None, keylist.pop());
     }
 
+    #[test]
+    fn remove_all() {
+        let mut keylist = data();
+
+        assert_eq!(Some(vec![1, 2]), keylist.remove_all(&"oke"));
+        assert_eq!(None, keylist.remove_all(&"oke"));
+        assert_eq!(HashKeylist::from(vec![("test", 19)]), keylist);
+    }
+
     #[test]
     #[should_panic(expected = "removal index (is 0) should be < len (is 0)")]
     fn remove_empty() {
@@ -813,9 +1205,16 @@ mod tests {
             Vec::from(keylist)
         )
     }
+
+    #[test]
+    fn from_array() {
+        let keylist = HashKeylist::from([("oke", 1), ("test", 19), ("oke", 2)]);
+
+        assert_eq!(keylist, data());
+    }
 }
 
-#[cfg(all(test, feature = "serde"))]
+#[cfg(all(test, feature = "std", feature = "serde"))]
 mod serde_tests {
     use crate::HashKeylist;
     use serde_test::{assert_de_tokens, assert_ser_tokens, assert_tokens, Token};
@@ -1,8 +1,10 @@
+pub use crate::iter::{IterKeyValue, RowIter, RowIterMut};
+
+use super::HashMap;
 use crate::HashKeylist;
-use std::collections::HashMap;
-use std::hash::{BuildHasher, Hash};
+use core::hash::{BuildHasher, Hash};
 
-impl<'a, K, V, S> IntoIterator for HashKeylist<K, V, S>
+impl<K, V, S> IntoIterator for HashKeylist<K, V, S>
 where
     K: Hash + Eq,
     V: Eq,
@@ -24,8 +26,8 @@ where
 }
 
 pub struct IntoIter<K, V> {
-    pub(crate) keys: std::vec::IntoIter<K>,
-    pub(crate) map: HashMap<K, std::vec::IntoIter<V>>,
+    pub(crate) keys: alloc::vec::IntoIter<K>,
+    pub(crate) map: HashMap<K, alloc::vec::IntoIter<V>>,
 }
 
 impl<K, V> Iterator for IntoIter<K, V>
@@ -42,7 +44,7 @@ where
 }
 
 pub struct IterMut<'a, K, V> {
-    pub(crate) keys: std::slice::Iter<'a, K>,
+    pub(crate) keys: core::slice::Iter<'a, K>,
     pub(crate) map: HashMap<&'a K, RowIterMut<'a, V>>,
 }
 
@@ -59,19 +61,8 @@ where
     }
 }
 
-pub struct RowIterMut<'a, V> {
-    pub(crate) values: std::slice::IterMut<'a, V>,
-}
-
-impl<'a, V> Iterator for RowIterMut<'a, V> {
-    type Item = &'a mut V;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.values.next()
-    }
-}
-
 pub struct Iter<'a, K, V> {
-    pub(crate) keys: std::slice::Iter<'a, K>,
+    pub(crate) keys: core::slice::Iter<'a, K>,
     pub(crate) map: HashMap<&'a K, RowIter<'a, V>>,
 }
 
@@ -87,26 +78,3 @@ where
         Some((key, value))
     }
 }
-
-pub struct RowIter<'a, V> {
-    pub(crate) values: std::slice::Iter<'a, V>,
-}
-
-impl<'a, V> Iterator for RowIter<'a, V> {
-    type Item = &'a V;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.values.next()
-    }
-}
-
-pub struct IterKeyValue<'a, K, V> {
-    pub(crate) key: &'a K,
-    pub(crate) values: std::slice::Iter<'a, V>,
-}
-
-impl<'a, K, V> Iterator for IterKeyValue<'a, K, V> {
-    type Item = (&'a K, &'a V);
-    fn next(&mut self) -> Option<Self::Item> {
-        Some((self.key, self.values.next()?))
-    }
-}
@@ -0,0 +1,434 @@
+//! HTTP header blocks, an ordered multimap with case-insensitive names.
+//!
+//! ```
+//! use keylist::headers::Headers;
+//!
+//! let headers: Headers = "Host: example.com\r\nAccept: text/html\r\naccept: application/json\r\n"
+//!     .parse()
+//!     .unwrap();
+//!
+//! assert_eq!(headers.get("host"), Some("example.com"));
+//! assert_eq!(headers.get_all("ACCEPT"), vec!["text/html", "application/json"]);
+//! assert_eq!(
+//!     headers.get_joined("Accept").as_deref(),
+//!     Some("text/html, application/json")
+//! );
+//!
+//! assert_eq!(
+//!     headers.to_string(),
+//!     "Host: example.com\r\nAccept: text/html\r\naccept: application/json\r\n"
+//! );
+//! ```
+
+use crate::HashKeylist;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// Header name that compares and hashes ASCII case-insensitively, but keeps its original casing.
+#[derive(Debug, Clone)]
+pub struct HeaderName(String);
+
+impl HeaderName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for HeaderName {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for HeaderName {}
+
+impl Hash for HeaderName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.0.bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+        state.write_u8(0xff);
+    }
+}
+
+impl From<&str> for HeaderName {
+    fn from(name: &str) -> Self {
+        HeaderName(name.to_string())
+    }
+}
+
+impl From<String> for HeaderName {
+    fn from(name: String) -> Self {
+        HeaderName(name)
+    }
+}
+
+impl fmt::Display for HeaderName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    MissingColon,
+    /// the name is empty or contains characters that are not allowed in a token
+    InvalidName,
+    /// RFC 9112 does not allow whitespace between the name and the colon
+    WhitespaceBeforeColon,
+    /// the value contains control characters like CR, LF or NUL
+    InvalidValue,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::MissingColon => f.write_str("missing colon after header name"),
+            ErrorKind::InvalidName => f.write_str("invalid header name"),
+            ErrorKind::WhitespaceBeforeColon => f.write_str("whitespace before colon"),
+            ErrorKind::InvalidValue => f.write_str("invalid header value"),
+        }
+    }
+}
+
+/// Error while parsing a header block, the line number is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl std::error::Error for ErrorKind {}
+
+fn is_token_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+fn validate_name(name: &str) -> Result<(), ErrorKind> {
+    if name.is_empty() || !name.bytes().all(is_token_char) {
+        return Err(ErrorKind::InvalidName);
+    }
+    Ok(())
+}
+
+fn validate_value(value: &str) -> Result<(), ErrorKind> {
+    if value.chars().any(|c| c.is_ascii_control() && c != '\t') {
+        return Err(ErrorKind::InvalidValue);
+    }
+    Ok(())
+}
+
+fn trim_whitespace(value: &str) -> &str {
+    value.trim_matches(|c| c == ' ' || c == '\t')
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Headers {
+    inner: HashKeylist<HeaderName, String, RandomState>,
+}
+
+impl Default for Headers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Headers {
+    pub fn new() -> Self {
+        Headers {
+            inner: HashKeylist::new(),
+        }
+    }
+
+    /// Parses header lines separated by `\r\n` (a bare `\n` is accepted too).
+    /// Parsing stops at the first empty line, which ends a header block.
+    ///
+    /// Obsolete line folding (a line starting with a space or tab) is replaced by a single space.
+    pub fn parse(block: &str) -> Result<Self, ParseError> {
+        let mut lines: Vec<(usize, String)> = Vec::new();
+        for (i, line) in block.split('\n').enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.is_empty() {
+                break;
+            }
+            match lines.last_mut() {
+                Some((_, previous)) if line.starts_with([' ', '\t']) => {
+                    previous.push(' ');
+                    previous.push_str(trim_whitespace(line));
+                }
+                _ => lines.push((i + 1, line.to_string())),
+            }
+        }
+
+        let mut headers = Headers::new();
+        for (line_number, line) in lines {
+            let error = |kind| ParseError {
+                line: line_number,
+                kind,
+            };
+            let pos = line
+                .find(':')
+                .ok_or_else(|| error(ErrorKind::MissingColon))?;
+            let name = &line[..pos];
+            if name.ends_with([' ', '\t']) {
+                return Err(error(ErrorKind::WhitespaceBeforeColon));
+            }
+            headers
+                .append(name, trim_whitespace(&line[pos + 1..]))
+                .map_err(error)?;
+        }
+        Ok(headers)
+    }
+
+    /// Adds the header at the end, after validating the name and value.
+    pub fn append(&mut self, name: &str, value: &str) -> Result<(), ErrorKind> {
+        validate_name(name)?;
+        validate_value(value)?;
+        self.inner
+            .push(HeaderName::from(name), trim_whitespace(value).to_string());
+        Ok(())
+    }
+
+    /// Replaces all values of the header with a single value, added at the end.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), ErrorKind> {
+        validate_name(name)?;
+        validate_value(value)?;
+        self.remove(name);
+        self.append(name, value)
+    }
+
+    /// Removes all values of the header, returns them in order.
+    pub fn remove(&mut self, name: &str) -> Vec<String> {
+        self.inner
+            .remove_all(&HeaderName::from(name))
+            .unwrap_or_default()
+    }
+
+    /// The first value of the header.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.inner.get(&HeaderName::from(name)).map(|x| x.as_str())
+    }
+
+    /// All values of the header, in the order they were received.
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        match self.inner.get_all(&HeaderName::from(name)) {
+            Some(values) => values.iter().map(|x| x.as_str()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// All values of the header joined by `", "`, like a list-valued header.
+    ///
+    /// Note that `Set-Cookie` can not be combined like this.
+    pub fn get_joined(&self, name: &str) -> Option<String> {
+        let values = self.inner.get_all(&HeaderName::from(name))?;
+        Some(values.join(", "))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.inner.get(&HeaderName::from(name)).is_some()
+    }
+
+    /// Iterates the headers in order, with the names as they were received.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.inner.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn as_keylist(&self) -> &HashKeylist<HeaderName, String, RandomState> {
+        &self.inner
+    }
+}
+
+impl FromStr for Headers {
+    type Err = ParseError;
+
+    fn from_str(block: &str) -> Result<Self, Self::Err> {
+        Headers::parse(block)
+    }
+}
+
+/// Writes every header as `Name: value\r\n`, without the empty line that ends the block.
+impl fmt::Display for Headers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in self.iter() {
+            write!(f, "{}: {}\r\n", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Headers> for HashKeylist<HeaderName, String, RandomState> {
+    fn from(headers: Headers) -> Self {
+        headers.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorKind, HeaderName, Headers, ParseError};
+
+    fn headers() -> Headers {
+        Headers::parse(
+            "Content-Type: text/plain\r\nCache-Control: no-cache\r\nSet-Cookie: a=1\r\ncache-control:  max-age=0 \r\nSET-COOKIE: b=2\r\n\r\n",
+        )
+        .unwrap()
+    }
+
+    fn error(input: &str) -> ParseError {
+        Headers::parse(input).unwrap_err()
+    }
+
+    #[test]
+    fn header_name() {
+        assert_eq!(
+            HeaderName::from("Content-Type"),
+            HeaderName::from("content-TYPE")
+        );
+        assert_ne!(
+            HeaderName::from("Content-Type"),
+            HeaderName::from("Content-Typ")
+        );
+    }
+
+    #[test]
+    fn get() {
+        let headers = headers();
+
+        assert_eq!(headers.get("content-type"), Some("text/plain"));
+        assert_eq!(headers.get("CACHE-CONTROL"), Some("no-cache"));
+        assert_eq!(headers.get("accept"), None);
+        assert!(headers.contains("Set-cookie"));
+        assert_eq!(headers.len(), 5);
+    }
+
+    #[test]
+    fn get_all() {
+        let headers = headers();
+
+        assert_eq!(headers.get_all("set-cookie"), vec!["a=1", "b=2"]);
+        assert_eq!(headers.get_all("accept"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn get_joined() {
+        let headers = headers();
+
+        assert_eq!(
+            headers.get_joined("Cache-Control").as_deref(),
+            Some("no-cache, max-age=0")
+        );
+        assert_eq!(headers.get_joined("accept"), None);
+    }
+
+    #[test]
+    fn preserves_casing_and_order() {
+        let headers = headers();
+
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            vec![
+                ("Content-Type", "text/plain"),
+                ("Cache-Control", "no-cache"),
+                ("Set-Cookie", "a=1"),
+                ("cache-control", "max-age=0"),
+                ("SET-COOKIE", "b=2"),
+            ]
+        );
+        assert_eq!(
+            headers.to_string(),
+            "Content-Type: text/plain\r\nCache-Control: no-cache\r\nSet-Cookie: a=1\r\ncache-control: max-age=0\r\nSET-COOKIE: b=2\r\n"
+        );
+        assert_eq!(Headers::parse(&headers.to_string()), Ok(headers));
+    }
+
+    #[test]
+    fn parse_stops_at_empty_line() {
+        let headers = Headers::parse("A: 1\nB: 2\n\nnot a header").unwrap();
+
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            vec![("A", "1"), ("B", "2")]
+        );
+        assert!(Headers::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn obsolete_line_folding() {
+        let headers = Headers::parse("X-Long: first\r\n  second\r\n\tthird\r\nB: 2").unwrap();
+
+        assert_eq!(headers.get("x-long"), Some("first second third"));
+        assert_eq!(headers.get("b"), Some("2"));
+    }
+
+    #[test]
+    fn empty_value() {
+        let headers = Headers::parse("X-Empty:\r\nX-Spaces:   \r\n").unwrap();
+
+        assert_eq!(headers.get("x-empty"), Some(""));
+        assert_eq!(headers.get("x-spaces"), Some(""));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            error("A: 1\r\nno colon\r\n"),
+            ParseError {
+                line: 2,
+                kind: ErrorKind::MissingColon
+            }
+        );
+        assert_eq!(
+            error("A: 1\r\nB : 2"),
+            ParseError {
+                line: 2,
+                kind: ErrorKind::WhitespaceBeforeColon
+            }
+        );
+        assert_eq!(error(": 1").kind, ErrorKind::InvalidName);
+        assert_eq!(error("Bad Name: 1").kind, ErrorKind::InvalidName);
+        assert_eq!(error("A: 1\r\nB: a\0b").kind, ErrorKind::InvalidValue);
+        assert_eq!(
+            error("A: 1\r\nB: a\rb").to_string(),
+            "line 2: invalid header value"
+        );
+    }
+
+    #[test]
+    fn append_and_set() {
+        let mut headers = Headers::new();
+        headers.append("Accept", "text/html").unwrap();
+        headers.append("X-Id", "1").unwrap();
+        headers.append("accept", "text/plain").unwrap();
+
+        assert_eq!(
+            headers.append("X-Bad", "a\r\nInjected: 1"),
+            Err(ErrorKind::InvalidValue)
+        );
+        assert_eq!(headers.append("X Bad", "1"), Err(ErrorKind::InvalidName));
+
+        headers.set("ACCEPT", "*/*").unwrap();
+
+        assert_eq!(headers.to_string(), "X-Id: 1\r\nACCEPT: */*\r\n");
+        assert_eq!(headers.remove("accept"), vec!["*/*".to_string()]);
+        assert_eq!(headers.remove("accept"), Vec::<String>::new());
+        assert_eq!(headers.len(), 1);
+    }
+}
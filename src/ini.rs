@@ -0,0 +1,852 @@
+//! Git-config and INI files, with repeated keys and sections.
+//!
+//! A file becomes a keylist of sections, each section is a keylist of its entries.
+//! Subsections are named like git does it: `[remote "origin"]` becomes `remote.origin`.
+//! Entries before the first section end up in a section with an empty name.
+//!
+//! A [`Document`] also keeps the comments and layout of the file, so it can be edited and written back.
+//! ```
+//! use keylist::ini::Document;
+//!
+//! let input = r#"# managed by hand
+//! [remote "origin"]
+//!     url = https://example.com/repo.git
+//!     fetch = +refs/heads/*:refs/remotes/origin/*
+//! "#;
+//!
+//! let mut document: Document = input.parse().unwrap();
+//! document.push("remote.origin", "fetch", "+refs/tags/*:refs/tags/*").unwrap();
+//!
+//! let sections = document.to_keylist();
+//! let origin = sections.get(&"remote.origin".to_string()).unwrap();
+//! assert_eq!(origin.get_all(&"fetch".to_string()).len(), 2);
+//!
+//! assert_eq!(
+//!     document.to_string(),
+//!     r#"# managed by hand
+//! [remote "origin"]
+//!     url = https://example.com/repo.git
+//!     fetch = +refs/heads/*:refs/remotes/origin/*
+//!     fetch = +refs/tags/*:refs/tags/*
+//! "#
+//! );
+//! ```
+//!
+//! Like git, section names and keys are looked up case-insensitively and subsection names case-sensitively,
+//! but the keylist keeps the names as written.
+//! A key without `=` is a boolean and gets the value `true`.
+
+use crate::VecKeylist;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+pub type Sections = VecKeylist<String, VecKeylist<String, String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidSection,
+    InvalidKey,
+    UnterminatedQuote,
+    InvalidEscape,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::InvalidSection => f.write_str("invalid section header"),
+            ErrorKind::InvalidKey => f.write_str("invalid key"),
+            ErrorKind::UnterminatedQuote => f.write_str("unterminated quote"),
+            ErrorKind::InvalidEscape => f.write_str("invalid escape sequence"),
+        }
+    }
+}
+
+/// Error while parsing, the line number is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Lines that were parsed keep their raw text, so they are written back exactly as they were read.
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    /// blank lines and comments
+    Other(String),
+    Section {
+        raw: Option<String>,
+        name: String,
+    },
+    Entry {
+        raw: Option<String>,
+        indent: String,
+        key: String,
+        value: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Document {
+    lines: Vec<Line>,
+}
+
+/// Compares `section.subsection` names, only the section part is case-insensitive.
+fn section_matches(a: &str, b: &str) -> bool {
+    let (a_section, a_sub) = split_section(a);
+    let (b_section, b_sub) = split_section(b);
+    a_section.eq_ignore_ascii_case(b_section) && a_sub == b_sub
+}
+
+fn split_section(name: &str) -> (&str, Option<&str>) {
+    match name.find('.') {
+        Some(pos) => (&name[..pos], Some(&name[pos + 1..])),
+        None => (name, None),
+    }
+}
+
+fn is_section_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '.' || c == '_'
+}
+
+fn is_key_char(c: char) -> bool {
+    !matches!(c, '"' | '#' | ';' | '[' | ']' | '=' | '\\')
+}
+
+/// Whether the key would be read back the same, so it can not break the line it is written on.
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.trim() == key && key.chars().all(|c| is_key_char(c) && !c.is_control())
+}
+
+/// Whether the section name can be written as a header. The subsection part gets quoted, but like git the parser
+/// only unescapes `\\` and `\"` there, so control characters would not be read back the same.
+fn is_valid_section(name: &str) -> bool {
+    let (section, sub) = split_section(name);
+    name.is_empty()
+        || (!section.is_empty()
+            && section.chars().all(is_section_char)
+            && !sub.unwrap_or_default().contains(char::is_control))
+}
+
+fn is_comment(rest: &str) -> bool {
+    let rest = rest.trim_start();
+    rest.is_empty() || rest.starts_with(['#', ';'])
+}
+
+fn write_quoted(buffer: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '\\' => buffer.push_str("\\\\"),
+            '"' => buffer.push_str("\\\""),
+            '\n' => buffer.push_str("\\n"),
+            '\t' => buffer.push_str("\\t"),
+            '\u{8}' => buffer.push_str("\\b"),
+            c => buffer.push(c),
+        }
+    }
+}
+
+fn render_section(name: &str) -> String {
+    match split_section(name) {
+        (section, Some(sub)) => {
+            let mut buffer = format!("[{} \"", section);
+            write_quoted(&mut buffer, sub);
+            buffer.push_str("\"]");
+            buffer
+        }
+        (section, None) => format!("[{}]", section),
+    }
+}
+
+fn render_entry(indent: &str, key: &str, value: &str) -> String {
+    let mut buffer = format!("{}{} = ", indent, key);
+    let needs_quotes = value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.contains(['#', ';']);
+    if needs_quotes {
+        buffer.push('"');
+    }
+    write_quoted(&mut buffer, value);
+    if needs_quotes {
+        buffer.push('"');
+    }
+    buffer
+}
+
+struct Parser<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            kind,
+        }
+    }
+
+    fn section(&self, header: &'a str) -> Result<(String, &'a str), ParseError> {
+        let mut chars = header.char_indices().skip(1).peekable();
+        let mut name = String::new();
+        while let Some(&(_, c)) = chars.peek() {
+            if !is_section_char(c) {
+                break;
+            }
+            name.push(c);
+            chars.next();
+        }
+        if name.is_empty() {
+            return Err(self.error(ErrorKind::InvalidSection));
+        }
+
+        match chars.next() {
+            Some((_, ']')) => (),
+            Some((_, ' ')) | Some((_, '\t')) => {
+                while matches!(chars.peek(), Some((_, ' ')) | Some((_, '\t'))) {
+                    chars.next();
+                }
+                if !matches!(chars.next(), Some((_, '"'))) {
+                    return Err(self.error(ErrorKind::InvalidSection));
+                }
+                name.push('.');
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => name.push(c),
+                            None => return Err(self.error(ErrorKind::UnterminatedQuote)),
+                        },
+                        Some((_, c)) => name.push(c),
+                        None => return Err(self.error(ErrorKind::UnterminatedQuote)),
+                    }
+                }
+                if !matches!(chars.next(), Some((_, ']'))) {
+                    return Err(self.error(ErrorKind::InvalidSection));
+                }
+            }
+            _ => return Err(self.error(ErrorKind::InvalidSection)),
+        }
+
+        let rest = match chars.next() {
+            Some((pos, _)) => &header[pos..],
+            None => "",
+        };
+        Ok((name, rest))
+    }
+
+    /// Parses a value, which can continue on the next lines when a line ends with a backslash.
+    fn value(&mut self, first: &'a str, raw: &mut String) -> Result<String, ParseError> {
+        let mut value = String::new();
+        let mut line = first.trim_start();
+        let mut quoted = false;
+        // length of the value without trailing whitespace outside quotes
+        let mut keep = 0;
+        loop {
+            let mut continued = false;
+            let mut chars = line.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => quoted = !quoted,
+                    '#' | ';' if !quoted => break,
+                    '\\' => match chars.next() {
+                        Some('n') => value.push('\n'),
+                        Some('t') => value.push('\t'),
+                        Some('b') => value.push('\u{8}'),
+                        Some(c @ '"') | Some(c @ '\\') => value.push(c),
+                        Some(_) => return Err(self.error(ErrorKind::InvalidEscape)),
+                        None => {
+                            continued = true;
+                            break;
+                        }
+                    },
+                    c if c.is_whitespace() && !quoted => {
+                        if !value.is_empty() {
+                            value.push(c);
+                        }
+                        continue;
+                    }
+                    c => value.push(c),
+                }
+                keep = value.len();
+            }
+
+            if !continued {
+                break;
+            }
+            match self.lines.next() {
+                Some((i, next)) => {
+                    self.line = i + 1;
+                    raw.push('\n');
+                    raw.push_str(next);
+                    line = next;
+                }
+                None => break,
+            }
+        }
+        if quoted {
+            return Err(self.error(ErrorKind::UnterminatedQuote));
+        }
+        value.truncate(keep);
+        Ok(value)
+    }
+
+    fn next_line(&mut self) -> Option<Result<Line, ParseError>> {
+        let (i, text) = self.lines.next()?;
+        self.line = i + 1;
+        let line = text.trim_start();
+        if is_comment(line) {
+            return Some(Ok(Line::Other(text.to_string())));
+        }
+
+        if line.starts_with('[') {
+            return Some(self.section(line).and_then(|(name, rest)| {
+                if !is_comment(rest) {
+                    return Err(self.error(ErrorKind::InvalidSection));
+                }
+                Ok(Line::Section {
+                    raw: Some(text.to_string()),
+                    name,
+                })
+            }));
+        }
+
+        let indent = text[..text.len() - line.len()].to_string();
+        let (key, rest) = match line.find(|c| !is_key_char(c)) {
+            Some(pos) => (&line[..pos], &line[pos..]),
+            None => (line, ""),
+        };
+        let key = key.trim_end();
+        if key.is_empty() {
+            return Some(Err(self.error(ErrorKind::InvalidKey)));
+        }
+
+        let mut raw = text.to_string();
+        let value = if let Some(value) = rest.strip_prefix('=') {
+            match self.value(value, &mut raw) {
+                Ok(value) => value,
+                Err(e) => return Some(Err(e)),
+            }
+        } else if is_comment(rest) {
+            "true".to_string()
+        } else {
+            return Some(Err(self.error(ErrorKind::InvalidKey)));
+        };
+
+        Some(Ok(Line::Entry {
+            raw: Some(raw),
+            indent,
+            key: key.to_string(),
+            value,
+        }))
+    }
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Document { lines: Vec::new() }
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            lines: input.lines().enumerate(),
+            line: 0,
+        };
+        let mut lines = Vec::new();
+        while let Some(line) = parser.next_line() {
+            lines.push(line?);
+        }
+        Ok(Document { lines })
+    }
+
+    /// Sections in file order, a section that occurs multiple times is in the list multiple times.
+    pub fn to_keylist(&self) -> Sections {
+        let mut sections = VecKeylist::new();
+        let mut current: Option<(String, VecKeylist<String, String>)> = None;
+        for line in &self.lines {
+            match line {
+                Line::Section { name, .. } => {
+                    if let Some((name, entries)) = current.take() {
+                        sections.push(name, entries);
+                    }
+                    current = Some((name.clone(), VecKeylist::new()));
+                }
+                Line::Entry { key, value, .. } => current
+                    .get_or_insert_with(|| (String::new(), VecKeylist::new()))
+                    .1
+                    .push(key.clone(), value.clone()),
+                Line::Other(_) => (),
+            }
+        }
+        if let Some((name, entries)) = current {
+            sections.push(name, entries);
+        }
+        sections
+    }
+
+    /// Entries as `(section, key, value)` with the index of their line.
+    fn entries(&self) -> impl Iterator<Item = (usize, &str, &str, &str)> {
+        let mut section = "";
+        self.lines
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, line)| match line {
+                Line::Section { name, .. } => {
+                    section = name;
+                    None
+                }
+                Line::Entry { key, value, .. } => Some((i, section, key.as_str(), value.as_str())),
+                Line::Other(_) => None,
+            })
+    }
+
+    fn matching<'a, 'b>(
+        &'a self,
+        section: &'b str,
+        key: &'b str,
+    ) -> impl Iterator<Item = (usize, &'a str)> + 'b
+    where
+        'a: 'b,
+    {
+        self.entries()
+            .filter(move |(_, s, k, _)| section_matches(s, section) && k.eq_ignore_ascii_case(key))
+            .map(|(i, _, _, v)| (i, v))
+    }
+
+    /// The last value of the key, git uses the last value when a key is repeated.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.matching(section, key).last().map(|(_, v)| v)
+    }
+
+    pub fn get_all(&self, section: &str, key: &str) -> Vec<&str> {
+        self.matching(section, key).map(|(_, v)| v).collect()
+    }
+
+    /// Adds the entry at the end of the last section with this name, the section is added if it does not exist.
+    ///
+    /// Fails if the key or the section name could not be parsed back, the value is quoted where needed.
+    pub fn push(&mut self, section: &str, key: &str, value: &str) -> Result<(), ErrorKind> {
+        if !is_valid_section(section) {
+            return Err(ErrorKind::InvalidSection);
+        }
+        if !is_valid_key(key) {
+            return Err(ErrorKind::InvalidKey);
+        }
+        // entries before the first section belong to the section without a name
+        let mut matched = section.is_empty();
+        let mut position = None;
+        let mut indent = if matched { "" } else { "\t" };
+        for (i, line) in self.lines.iter().enumerate() {
+            match line {
+                Line::Section { name, .. } => {
+                    matched = section_matches(name, section);
+                    if matched {
+                        position = Some(i + 1);
+                    }
+                }
+                Line::Entry { indent: x, .. } if matched => {
+                    position = Some(i + 1);
+                    indent = x;
+                }
+                _ => (),
+            }
+        }
+        if section.is_empty() && position.is_none() {
+            position = Some(0);
+        }
+
+        let entry = Line::Entry {
+            raw: None,
+            indent: indent.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        match position {
+            Some(position) => self.lines.insert(position, entry),
+            None => {
+                self.lines.push(Line::Section {
+                    raw: None,
+                    name: section.to_string(),
+                });
+                self.lines.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the value of the last occurrence of the key, or adds it if there is none.
+    ///
+    /// Fails like `push` does.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) -> Result<(), ErrorKind> {
+        if !is_valid_key(key) {
+            return Err(ErrorKind::InvalidKey);
+        }
+        match self.matching(section, key).last().map(|(i, _)| i) {
+            Some(i) => {
+                if let Line::Entry {
+                    raw, value: old, ..
+                } = &mut self.lines[i]
+                {
+                    *raw = None;
+                    *old = value.to_string();
+                }
+                Ok(())
+            }
+            None => self.push(section, key, value),
+        }
+    }
+
+    /// Removes all occurrences of the key in the matching sections, returns the removed values.
+    pub fn remove_all(&mut self, section: &str, key: &str) -> Vec<String> {
+        let positions: Vec<usize> = self.matching(section, key).map(|(i, _)| i).collect();
+        let mut values = Vec::with_capacity(positions.len());
+        // remove from the back, so the positions stay valid
+        for i in positions.into_iter().rev() {
+            if let Line::Entry { value, .. } = self.lines.remove(i) {
+                values.push(value);
+            }
+        }
+        values.reverse();
+        values
+    }
+}
+
+impl FromStr for Document {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Document::parse(input)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                Line::Other(raw)
+                | Line::Section { raw: Some(raw), .. }
+                | Line::Entry { raw: Some(raw), .. } => f.write_str(raw)?,
+                Line::Section { raw: None, name } => f.write_str(&render_section(name))?,
+                Line::Entry {
+                    raw: None,
+                    indent,
+                    key,
+                    value,
+                } => f.write_str(&render_entry(indent, key, value))?,
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Fails with `ErrorKind::InvalidKey` or `ErrorKind::InvalidSection` for names that would not parse back,
+/// entries without a section can only be written before the first section, so an unnamed section is only
+/// allowed first.
+impl TryFrom<&Sections> for Document {
+    type Error = ErrorKind;
+
+    fn try_from(sections: &Sections) -> Result<Self, Self::Error> {
+        let mut lines = Vec::new();
+        for (i, (name, entries)) in sections.iter().enumerate() {
+            if (name.is_empty() && i > 0) || !is_valid_section(name) {
+                return Err(ErrorKind::InvalidSection);
+            }
+            let indent = if name.is_empty() {
+                ""
+            } else {
+                lines.push(Line::Section {
+                    raw: None,
+                    name: name.clone(),
+                });
+                "\t"
+            };
+            for (key, value) in entries.iter() {
+                if !is_valid_key(key) {
+                    return Err(ErrorKind::InvalidKey);
+                }
+                lines.push(Line::Entry {
+                    raw: None,
+                    indent: indent.to_string(),
+                    key: key.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+        Ok(Document { lines })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Sections, ParseError> {
+    Ok(Document::parse(input)?.to_keylist())
+}
+
+/// Fails like `Document::try_from` does.
+pub fn to_string(sections: &Sections) -> Result<String, ErrorKind> {
+    Ok(Document::try_from(sections)?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, to_string, Document, ErrorKind, ParseError, Sections};
+    use crate::VecKeylist;
+
+    const CONFIG: &str = r#"; global settings
+name = top level
+
+[core]
+	bare = false
+	# trailing comment
+	editor = "vim -u NONE" ; inline comment
+	hooksPath
+
+[remote "origin"]
+	url = https://example.com/repo.git
+	fetch = +refs/heads/*:refs/remotes/origin/*
+	fetch = +refs/tags/*:refs/tags/*
+
+[Core]
+	bare = true
+"#;
+
+    fn sections(input: Vec<(&str, Vec<(&str, &str)>)>) -> Sections {
+        input
+            .into_iter()
+            .map(|(name, entries)| {
+                (
+                    name.to_string(),
+                    entries
+                        .into_iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn error(input: &str) -> (usize, ErrorKind) {
+        let err = parse(input).unwrap_err();
+        (err.line, err.kind)
+    }
+
+    #[test]
+    fn parse_config() {
+        let expected = sections(vec![
+            ("", vec![("name", "top level")]),
+            (
+                "core",
+                vec![
+                    ("bare", "false"),
+                    ("editor", "vim -u NONE"),
+                    ("hooksPath", "true"),
+                ],
+            ),
+            (
+                "remote.origin",
+                vec![
+                    ("url", "https://example.com/repo.git"),
+                    ("fetch", "+refs/heads/*:refs/remotes/origin/*"),
+                    ("fetch", "+refs/tags/*:refs/tags/*"),
+                ],
+            ),
+            ("Core", vec![("bare", "true")]),
+        ]);
+
+        assert_eq!(parse(CONFIG), Ok(expected));
+    }
+
+    #[test]
+    fn write_back_unchanged() {
+        let document: Document = CONFIG.parse().unwrap();
+
+        assert_eq!(document.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn get() {
+        let document: Document = CONFIG.parse().unwrap();
+
+        assert_eq!(document.get("core", "BARE"), Some("true"));
+        assert_eq!(document.get_all("CORE", "bare"), vec!["false", "true"]);
+        assert_eq!(
+            document.get("remote.origin", "url"),
+            Some("https://example.com/repo.git")
+        );
+        assert_eq!(document.get("remote.Origin", "url"), None);
+        assert_eq!(document.get("", "name"), Some("top level"));
+    }
+
+    #[test]
+    fn edit() {
+        let mut document: Document = CONFIG.parse().unwrap();
+        document.set("core", "bare", "no").unwrap();
+        document.set("core", "editor", " spaced # value ").unwrap();
+        document
+            .push("remote.origin", "pushurl", "git@example.com:repo.git")
+            .unwrap();
+        document.push("remote.up\"stream", "url", "a\\b").unwrap();
+        document.push("", "email", "me@example.com").unwrap();
+        assert_eq!(
+            document.remove_all("remote.origin", "fetch"),
+            vec![
+                "+refs/heads/*:refs/remotes/origin/*".to_string(),
+                "+refs/tags/*:refs/tags/*".to_string()
+            ]
+        );
+
+        let expected = r#"; global settings
+name = top level
+email = me@example.com
+
+[core]
+	bare = false
+	# trailing comment
+	editor = " spaced # value "
+	hooksPath
+
+[remote "origin"]
+	url = https://example.com/repo.git
+	pushurl = git@example.com:repo.git
+
+[Core]
+	bare = no
+[remote "up\"stream"]
+	url = a\\b
+"#;
+        assert_eq!(document.to_string(), expected);
+        assert_eq!(
+            Document::parse(expected).unwrap().to_keylist(),
+            document.to_keylist()
+        );
+    }
+
+    #[test]
+    fn edit_invalid_names() {
+        let mut document: Document = CONFIG.parse().unwrap();
+
+        for key in &["", "a\nb", "a = b", "[evil]", "#x", " x", "x\t"] {
+            assert_eq!(document.push("core", key, "1"), Err(ErrorKind::InvalidKey));
+            assert_eq!(document.set("core", key, "1"), Err(ErrorKind::InvalidKey));
+        }
+        for section in &["a]\n[b", ".x", "a b", "remote.a\nb", "remote.a\tb"] {
+            assert_eq!(
+                document.push(section, "x", "1"),
+                Err(ErrorKind::InvalidSection)
+            );
+        }
+        assert_eq!(document.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn quoting_and_escapes() {
+        let input = "[a]\n\tx = \"  padded  \"\n\ty = tab\\there \"#not comment\" # comment\n\tz = a  b   \n";
+        let expected = sections(vec![(
+            "a",
+            vec![
+                ("x", "  padded  "),
+                ("y", "tab\there #not comment"),
+                ("z", "a  b"),
+            ],
+        )]);
+
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed, expected);
+        assert_eq!(parse(&to_string(&parsed).unwrap()), Ok(parsed));
+    }
+
+    #[test]
+    fn continuation() {
+        let input = "[a]\n\tx = first \\\n  second\n\ty = 1\n";
+        let expected = sections(vec![("a", vec![("x", "first   second"), ("y", "1")])]);
+
+        assert_eq!(parse(input), Ok(expected));
+        assert_eq!(Document::parse(input).unwrap().to_string(), input);
+    }
+
+    #[test]
+    fn subsections() {
+        let input = "[branch \"feature/a.b\"]\n\tremote = origin\n[section.old]\n\tkey = 1\n";
+        let expected = sections(vec![
+            ("branch.feature/a.b", vec![("remote", "origin")]),
+            ("section.old", vec![("key", "1")]),
+        ]);
+
+        assert_eq!(parse(input), Ok(expected));
+    }
+
+    #[test]
+    fn subsection_round_trip() {
+        let input = sections(vec![("remote.a \"b\" \\c.d", vec![("url", "x")])]);
+
+        assert_eq!(
+            to_string(&input).unwrap(),
+            "[remote \"a \\\"b\\\" \\\\c.d\"]\n\turl = x\n"
+        );
+        assert_eq!(parse(&to_string(&input).unwrap()), Ok(input));
+        for name in &["remote.a\nb", "remote.a\tb", "remote.a\u{8}b"] {
+            let input = sections(vec![(name, vec![("url", "x")])]);
+            assert_eq!(to_string(&input), Err(ErrorKind::InvalidSection));
+        }
+    }
+
+    #[test]
+    fn to_string_keylist() {
+        let input = sections(vec![
+            ("", vec![("root", "1")]),
+            ("remote.origin", vec![("fetch", "a"), ("fetch", "b")]),
+        ]);
+
+        assert_eq!(
+            to_string(&input).unwrap(),
+            "root = 1\n[remote \"origin\"]\n\tfetch = a\n\tfetch = b\n"
+        );
+        assert_eq!(parse(&to_string(&input).unwrap()), Ok(input));
+        assert_eq!(to_string(&VecKeylist::new()).unwrap(), "");
+    }
+
+    #[test]
+    fn to_string_invalid_names() {
+        let injected = sections(vec![
+            ("core", vec![("k\n[evil]\nx", "1")]),
+            ("", vec![("top", "2")]),
+        ]);
+        let unnamed_last = sections(vec![("core", vec![("k", "1")]), ("", vec![("top", "2")])]);
+        let bad_section = sections(vec![("a]\n[b", vec![("k", "1")])]);
+
+        assert_eq!(to_string(&injected), Err(ErrorKind::InvalidKey));
+        assert_eq!(to_string(&unnamed_last), Err(ErrorKind::InvalidSection));
+        assert_eq!(to_string(&bad_section), Err(ErrorKind::InvalidSection));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("[a]\n[b"), (2, ErrorKind::InvalidSection));
+        assert_eq!(error("[]"), (1, ErrorKind::InvalidSection));
+        assert_eq!(error("[a \"b]"), (1, ErrorKind::UnterminatedQuote));
+        assert_eq!(error("[a] x = 1"), (1, ErrorKind::InvalidSection));
+        assert_eq!(error("[a]\n\n = 1"), (3, ErrorKind::InvalidKey));
+        assert_eq!(error("[a]\nkey \"x\""), (2, ErrorKind::InvalidKey));
+        assert_eq!(
+            error("[a]\nx = \"open\ny = 1"),
+            (2, ErrorKind::UnterminatedQuote)
+        );
+        assert_eq!(error("[a]\nx = \\q"), (2, ErrorKind::InvalidEscape));
+        assert_eq!(error("[a]\nx = a \\\nb \\q"), (3, ErrorKind::InvalidEscape));
+        assert_eq!(
+            ParseError {
+                line: 4,
+                kind: ErrorKind::InvalidKey
+            }
+            .to_string(),
+            "line 4: invalid key"
+        );
+    }
+}
@@ -0,0 +1,35 @@
+//! Iterators over the values of a single key, shared by the keylists that group values per key.
+
+pub struct RowIterMut<'a, V> {
+    pub(crate) values: core::slice::IterMut<'a, V>,
+}
+
+impl<'a, V> Iterator for RowIterMut<'a, V> {
+    type Item = &'a mut V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.values.next()
+    }
+}
+
+pub struct RowIter<'a, V> {
+    pub(crate) values: core::slice::Iter<'a, V>,
+}
+
+impl<'a, V> Iterator for RowIter<'a, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.values.next()
+    }
+}
+
+pub struct IterKeyValue<'a, K, V> {
+    pub(crate) key: &'a K,
+    pub(crate) values: core::slice::Iter<'a, V>,
+}
+
+impl<'a, K, V> Iterator for IterKeyValue<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.key, self.values.next()?))
+    }
+}
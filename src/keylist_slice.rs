@@ -0,0 +1,425 @@
+//! A borrowed keylist, `KeylistSlice` is to `VecKeylist` what `[T]` is to `Vec<T>`.
+//!
+//! `VecKeylist` derefs to it, so functions that only read a keylist can take a `&KeylistSlice` and accept a part
+//! of a keylist or any `&[(K, V)]` as well.
+#![cfg_attr(feature = "alloc", doc = "```")]
+#![cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+//! use keylist::{KeylistSlice, VecKeylist};
+//!
+//! fn first_port(options: &KeylistSlice<&str, u16>) -> Option<u16> {
+//!     options.get(&"port").copied()
+//! }
+//!
+//! let keylist = VecKeylist(vec![("host", 1), ("port", 80), ("port", 8080)]);
+//! assert_eq!(first_port(&keylist), Some(80));
+//! assert_eq!(first_port(&keylist[2..]), Some(8080));
+//! assert_eq!(first_port(KeylistSlice::new(&[("port", 443)])), Some(443));
+//! ```
+//!
+//! It does not need `alloc`, the methods that return a `Vec` are left out without it.
+
+#[cfg(feature = "alloc")]
+use crate::VecKeylist;
+#[cfg(feature = "alloc")]
+use alloc::borrow::{Borrow, BorrowMut, ToOwned};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{Index, IndexMut};
+use core::slice::{self, SliceIndex};
+
+/// Stable sort, `sort_by` on slices needs an allocator so without `alloc` this is an in-place insertion sort.
+fn stable_sort_by<T, F: FnMut(&T, &T) -> Ordering>(list: &mut [T], mut compare: F) {
+    #[cfg(feature = "alloc")]
+    list.sort_by(&mut compare);
+    #[cfg(not(feature = "alloc"))]
+    for i in 1..list.len() {
+        let mut j = i;
+        while j > 0 && compare(&list[j], &list[j - 1]) == Ordering::Less {
+            list.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct KeylistSlice<K, V>([(K, V)]);
+
+impl<K, V> KeylistSlice<K, V> {
+    pub fn new(pairs: &[(K, V)]) -> &Self {
+        // SAFETY: `KeylistSlice` is a `repr(transparent)` wrapper around `[(K, V)]`.
+        unsafe { &*(pairs as *const [(K, V)] as *const Self) }
+    }
+
+    pub fn new_mut(pairs: &mut [(K, V)]) -> &mut Self {
+        // SAFETY: `KeylistSlice` is a `repr(transparent)` wrapper around `[(K, V)]`.
+        unsafe { &mut *(pairs as *mut [(K, V)] as *mut Self) }
+    }
+
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.0
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [(K, V)] {
+        &mut self.0
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, (K, V)> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, (K, V)> {
+        self.0.iter_mut()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn keys_mut(&mut self) -> impl Iterator<Item = &mut K> {
+        self.iter_mut().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.iter_mut().map(|(_, v)| v)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<K: PartialEq, V> KeylistSlice<K, V> {
+    pub fn get_key_value(&self, key: &K) -> Option<&(K, V)> {
+        self.iter().find(|x| &x.0 == key)
+    }
+
+    pub fn get_key_value_mut(&mut self, key: &K) -> Option<&mut (K, V)> {
+        self.iter_mut().find(|x| &x.0 == key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let (_, v) = self.get_key_value(key)?;
+        Some(v)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let (_, v) = self.get_key_value_mut(key)?;
+        Some(v)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn get_all_get_key_value(&self, key: &K) -> Vec<&(K, V)> {
+        self.iter().filter(|(k, _)| k == key).collect()
+    }
+
+    /// get all values matching the key
+    #[cfg(feature = "alloc")]
+    pub fn get_all(&self, key: &K) -> Vec<&V> {
+        self.iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .collect()
+    }
+}
+
+impl<K, V: PartialEq> KeylistSlice<K, V> {
+    /// first pair with the value, O(n)
+    pub fn find_by_value(&self, value: &V) -> Option<&(K, V)> {
+        self.iter().find(|x| &x.1 == value)
+    }
+
+    /// get all keys that have the value, in order, O(n)
+    #[cfg(feature = "alloc")]
+    pub fn keys_for_value(&self, value: &V) -> Vec<&K> {
+        self.iter()
+            .filter(|(_, v)| v == value)
+            .map(|(k, _)| k)
+            .collect()
+    }
+}
+
+impl<K: Ord, V> KeylistSlice<K, V> {
+    pub fn sort_by_key(&mut self) {
+        stable_sort_by(&mut self.0, |a, b| a.0.cmp(&b.0))
+    }
+}
+
+impl<K, V: Ord> KeylistSlice<K, V> {
+    pub fn sort_by_value(&mut self) {
+        stable_sort_by(&mut self.0, |a, b| a.1.cmp(&b.1))
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> KeylistSlice<K, V> {
+    pub fn contains(&self, item: &(K, V)) -> bool {
+        self.0.contains(item)
+    }
+}
+
+impl<K: Ord, V: Ord> KeylistSlice<K, V> {
+    pub fn sort(&mut self) {
+        stable_sort_by(&mut self.0, Ord::cmp)
+    }
+
+    /// The normal get function uses a find on a iterator to find the key value.
+    /// This function uses binary search to find the key value, the first one if the key is duplicated.
+    /// The keylist has to be sorted, see `SortedKeylist` for a keylist that stays sorted.
+    pub fn get_key_value_sorted(&self, key: &K) -> Option<&(K, V)> {
+        let index = self.0.partition_point(|(a, _)| a < key);
+        self.0.get(index).filter(|(a, _)| a == key)
+    }
+
+    /// The normal get function uses a find on a iterator to find the value.
+    /// This function uses binary search to find the value
+    pub fn get_sorted(&self, key: &K) -> Option<&V> {
+        let (_, v) = self.get_key_value_sorted(key)?;
+        Some(v)
+    }
+}
+
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for KeylistSlice<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Hashes the same as the `VecKeylist` it was borrowed from.
+impl<K: Hash, V: Hash> Hash for KeylistSlice<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+/// Slicing by range gives a `KeylistSlice` again.
+impl<K, V, I> Index<I> for KeylistSlice<K, V>
+where
+    I: SliceIndex<[(K, V)], Output = [(K, V)]>,
+{
+    type Output = KeylistSlice<K, V>;
+
+    fn index(&self, index: I) -> &Self::Output {
+        KeylistSlice::new(&self.0[index])
+    }
+}
+
+impl<K, V, I> IndexMut<I> for KeylistSlice<K, V>
+where
+    I: SliceIndex<[(K, V)], Output = [(K, V)]>,
+{
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        KeylistSlice::new_mut(&mut self.0[index])
+    }
+}
+
+impl<'a, K, V> From<&'a [(K, V)]> for &'a KeylistSlice<K, V> {
+    fn from(pairs: &'a [(K, V)]) -> Self {
+        KeylistSlice::new(pairs)
+    }
+}
+
+impl<'a, K, V> From<&'a mut [(K, V)]> for &'a mut KeylistSlice<K, V> {
+    fn from(pairs: &'a mut [(K, V)]) -> Self {
+        KeylistSlice::new_mut(pairs)
+    }
+}
+
+impl<K, V> AsRef<[(K, V)]> for KeylistSlice<K, V> {
+    fn as_ref(&self) -> &[(K, V)] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Clone, V: Clone> ToOwned for KeylistSlice<K, V> {
+    type Owned = VecKeylist<K, V>;
+
+    fn to_owned(&self) -> VecKeylist<K, V> {
+        VecKeylist(self.0.to_vec())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V> Borrow<KeylistSlice<K, V>> for VecKeylist<K, V> {
+    fn borrow(&self) -> &KeylistSlice<K, V> {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V> BorrowMut<KeylistSlice<K, V>> for VecKeylist<K, V> {
+    fn borrow_mut(&mut self) -> &mut KeylistSlice<K, V> {
+        self
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a KeylistSlice<K, V> {
+    type Item = &'a (K, V);
+    type IntoIter = slice::Iter<'a, (K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut KeylistSlice<K, V> {
+    type Item = &'a mut (K, V);
+    type IntoIter = slice::IterMut<'a, (K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<[(K, V)]> for KeylistSlice<K, V> {
+    fn eq(&self, other: &[(K, V)]) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: PartialEq, V: PartialEq> PartialEq<VecKeylist<K, V>> for KeylistSlice<K, V> {
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        self.0 == other.0[..]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: PartialEq, V: PartialEq> PartialEq<KeylistSlice<K, V>> for VecKeylist<K, V> {
+    fn eq(&self, other: &KeylistSlice<K, V>) -> bool {
+        self.0[..] == other.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: serde::Serialize, V: serde::Serialize> serde::Serialize for KeylistSlice<K, V> {
+    /// Same as `VecKeylist`.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct("VecKeylist", &self.0)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{KeylistSlice, VecKeylist};
+    use std::borrow::ToOwned;
+
+    fn keylist() -> VecKeylist<&'static str, i32> {
+        VecKeylist(vec![("b", 2), ("a", 5), ("b", 1), ("c", 3)])
+    }
+
+    #[test]
+    fn from_slice() {
+        let pairs = [("a", 1), ("b", 2), ("a", 3)];
+        let slice = KeylistSlice::new(&pairs);
+
+        assert_eq!(slice.get(&"a"), Some(&1));
+        assert_eq!(slice.get_all(&"a"), vec![&1, &3]);
+        assert_eq!(slice.len(), 3);
+
+        let slice: &KeylistSlice<_, _> = pairs[1..].into();
+        assert_eq!(slice.get(&"a"), Some(&3));
+        assert!(*slice == pairs[1..]);
+    }
+
+    #[test]
+    fn range() {
+        let keylist = keylist();
+
+        assert_eq!(keylist[1..3], VecKeylist(vec![("a", 5), ("b", 1)]));
+        assert_eq!(keylist[..1].get(&"b"), Some(&2));
+        assert_eq!(keylist[1..].get(&"b"), Some(&1));
+        assert_eq!(keylist[2..].get(&"a"), None);
+        assert!(keylist[4..].is_empty());
+    }
+
+    #[test]
+    fn in_place() {
+        let mut keylist = keylist();
+
+        keylist[1..].sort_by_key();
+        assert_eq!(
+            keylist,
+            VecKeylist(vec![("b", 2), ("a", 5), ("b", 1), ("c", 3)])
+        );
+
+        keylist[..3].sort_by_key();
+        assert_eq!(
+            keylist,
+            VecKeylist(vec![("a", 5), ("b", 2), ("b", 1), ("c", 3)])
+        );
+        assert_eq!(keylist.get_sorted(&"b"), Some(&2));
+
+        for (_, v) in keylist[1..3].iter_mut() {
+            *v *= 10;
+        }
+        assert_eq!(
+            keylist.values().copied().collect::<Vec<_>>(),
+            vec![5, 20, 10, 3]
+        );
+
+        let mut pairs = [(2, "b"), (1, "a")];
+        KeylistSlice::new_mut(&mut pairs).sort();
+        assert_eq!(pairs, [(1, "a"), (2, "b")]);
+    }
+
+    #[test]
+    fn to_owned() {
+        let keylist = keylist();
+        let owned: VecKeylist<_, _> = keylist[2..].to_owned();
+
+        assert_eq!(owned, VecKeylist(vec![("b", 1), ("c", 3)]));
+        assert_eq!(format!("{:?}", &keylist[..1]), r#"[("b", 2)]"#);
+    }
+
+    #[test]
+    fn hash_like_vec_keylist() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(keylist());
+
+        let slice: &KeylistSlice<_, _> = &keylist();
+        assert!(set.contains(slice));
+        assert!(!set.contains(&keylist()[1..]));
+    }
+}
+
+#[cfg(all(test, feature = "alloc", feature = "serde"))]
+mod serde_tests {
+    use crate::VecKeylist;
+    use serde_test::{assert_ser_tokens, Token};
+
+    #[test]
+    fn serde_ser_range() {
+        let keylist = VecKeylist(vec![("oke", 1), ("test", 15)]);
+
+        assert_ser_tokens(
+            &keylist[1..],
+            &[
+                Token::NewtypeStruct { name: "VecKeylist" },
+                Token::Seq { len: Some(1) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
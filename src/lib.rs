@@ -4,7 +4,8 @@
 //! Because it is just a list with tuples, keys can be anything that can be put in a tuple. Also means that getting a value is not efficient.
 //!
 //! For something more usefull and quite similar take a look at: https://docs.rs/multimap
-//! ```
+#![cfg_attr(feature = "alloc", doc = "```")]
+#![cfg_attr(not(feature = "alloc"), doc = "```ignore")]
 //! use keylist::Keylist;
 //!
 //! let mut keylist = Keylist::from(vec![("a", 5), ("b", 2), ("a", 1)]);
@@ -44,7 +45,8 @@
 //! ```
 //!
 //! Convert keylist to map and back:
-//! ```
+#![cfg_attr(feature = "alloc", doc = "```")]
+#![cfg_attr(not(feature = "alloc"), doc = "```ignore")]
 //! use std::collections::HashMap;
 //! use std::iter::FromIterator;
 //! use keylist::Keylist;
@@ -63,7 +65,8 @@
 //! ```
 //!
 //! Arbitrary example:
-//! ```
+#![cfg_attr(feature = "alloc", doc = "```")]
+#![cfg_attr(not(feature = "alloc"), doc = "```ignore")]
 //! use keylist::Keylist;
 //!
 //! let mut keylist = Keylist::new();
@@ -77,7 +80,8 @@
 //!
 //! ## HashKeylist
 //! There is also a more efficient implemetation, backend by a hashmap, therefore the keys should be hashable.
-//! ```
+#![cfg_attr(feature = "std", doc = "```")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
 //! use keylist::HashKeylist;
 //! use std::collections::HashMap;
 //! use std::iter::FromIterator;
@@ -176,11 +180,100 @@
 //!     Vec::from(keylist)
 //! )
 //! ```
+//!
+//! ## no_std
+//! The `std` feature is on by default. Without it the crate only needs `alloc`, through the `alloc` feature:
+//! `VecKeylist`, `SortedKeylist`, `BTreeKeylist`, `SmallKeylist`, `StaticKeylist`, `PhfKeylist` and the display
+//! styles keep working. `HashKeylist` and `AdaptiveKeylist` need a hash map, with the `hashbrown` feature they are
+//! backed by `hashbrown` with a hasher of your choice. Of the formats only `elixir` is available, the other modules
+//! need `std`.
+//! ```toml
+//! keylist = { version = "0.3", default-features = false, features = ["hashbrown"] }
+//! ```
+//!
+//! Without `alloc` only `ArrayKeylist` and `KeylistSlice` are left, for targets that have no allocator at all.
+//! ```toml
+//! keylist = { version = "0.3", default-features = false, features = ["heapless"] }
+//! ```
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub mod adaptive_keylist;
+#[cfg(feature = "heapless")]
+pub mod array_keylist;
+#[cfg(feature = "std")]
+pub mod bi_keylist;
+#[cfg(feature = "alloc")]
+pub mod btree_keylist;
+#[cfg(feature = "std")]
+pub mod convert;
+#[cfg(feature = "std")]
+pub mod csv;
+#[cfg(feature = "alloc")]
+pub mod display;
+#[cfg(feature = "std")]
+pub mod dotenv;
+#[cfg(feature = "alloc")]
+pub mod elixir;
+#[cfg(feature = "etf")]
+pub mod etf;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
 pub mod hash_keylist;
+#[cfg(feature = "std")]
+pub mod headers;
+#[cfg(feature = "std")]
+pub mod ini;
+#[cfg(feature = "alloc")]
+mod iter;
+pub mod keylist_slice;
+#[cfg(feature = "alloc")]
+mod number;
+#[cfg(feature = "std")]
+pub mod options;
+#[cfg(feature = "alloc")]
+pub mod phf_keylist;
+#[cfg(feature = "properties")]
+pub mod properties;
+#[cfg(feature = "std")]
+pub mod query;
+#[cfg(feature = "std")]
+pub mod redacted;
+#[cfg(feature = "smallvec")]
+pub mod small_keylist;
+#[cfg(feature = "alloc")]
+pub mod sorted_keylist;
+#[cfg(feature = "alloc")]
+pub mod static_keylist;
+#[cfg(feature = "std")]
+pub mod unordered;
+#[cfg(feature = "alloc")]
 pub mod vec_keylist;
 
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use adaptive_keylist::AdaptiveKeylist;
+#[cfg(feature = "heapless")]
+pub use array_keylist::ArrayKeylist;
+#[cfg(feature = "std")]
+pub use bi_keylist::BiKeylist;
+#[cfg(feature = "alloc")]
+pub use btree_keylist::BTreeKeylist;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
 pub use hash_keylist::HashKeylist;
+pub use keylist_slice::KeylistSlice;
+#[cfg(feature = "alloc")]
+pub use phf_keylist::PhfKeylist;
+#[cfg(feature = "smallvec")]
+pub use small_keylist::SmallKeylist;
+#[cfg(feature = "alloc")]
+pub use sorted_keylist::SortedKeylist;
+#[cfg(feature = "alloc")]
+pub use static_keylist::StaticKeylist;
+#[cfg(feature = "alloc")]
 pub use vec_keylist::VecKeylist;
 
+#[cfg(feature = "alloc")]
 pub type Keylist<K, V> = VecKeylist<K, V>;
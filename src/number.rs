@@ -0,0 +1,57 @@
+//! Lexing of plain numbers, shared by the display styles and the option parser.
+
+/// Checks for a JSON number, returns whether it has a fraction and whether it has an exponent.
+pub(crate) fn number_parts(text: &str) -> Option<(bool, bool)> {
+    fn digits(text: &str) -> usize {
+        text.bytes().take_while(u8::is_ascii_digit).count()
+    }
+
+    let text = text.strip_prefix('-').unwrap_or(text);
+    let int = digits(text);
+    if int == 0 || (int > 1 && text.starts_with('0')) {
+        return None;
+    }
+    let mut rest = &text[int..];
+
+    let fraction = rest.starts_with('.');
+    if fraction {
+        let n = digits(&rest[1..]);
+        if n == 0 {
+            return None;
+        }
+        rest = &rest[1 + n..];
+    }
+
+    let exponent = rest.starts_with(['e', 'E']);
+    if exponent {
+        let sign = rest[1..].strip_prefix(['+', '-']).unwrap_or(&rest[1..]);
+        let n = digits(sign);
+        if n == 0 {
+            return None;
+        }
+        rest = &sign[n..];
+    }
+
+    if rest.is_empty() {
+        Some((fraction, exponent))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::number_parts;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number_parts("0"), Some((false, false)));
+        assert_eq!(number_parts("-0.5"), Some((true, false)));
+        assert_eq!(number_parts("2E-3"), Some((false, true)));
+        for text in &[
+            "", "-", "01", "1.", ".5", "1e", "1e+", "NaN", "inf", "1_000", "0x1",
+        ] {
+            assert_eq!(number_parts(text), None, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,465 @@
+//! Command-line parsing in the style of Elixir's `OptionParser`.
+//!
+//! Switches are collected in a keylist, in the order they were given. Dashes in switch names
+//! become underscores, so `--dry-run` is stored as `dry_run`.
+//! ```
+//! use keylist::options::{Kind, OptionValue, Spec};
+//!
+//! let spec = Spec::new()
+//!     .switch("dry_run", Kind::Boolean)
+//!     .switch("verbose", Kind::Count)
+//!     .keep("include", Kind::String)
+//!     .alias('v', "verbose");
+//!
+//! let parsed = spec.parse(vec!["-v", "--include", "src", "build", "--no-dry-run", "-v", "--include=tests"]);
+//!
+//! assert_eq!(parsed.options.get(&"verbose".to_string()), Some(&OptionValue::Integer(2)));
+//! assert_eq!(parsed.options.get(&"dry_run".to_string()), Some(&OptionValue::Boolean(false)));
+//! assert_eq!(
+//!     parsed.options.get_all(&"include".to_string()),
+//!     vec![&OptionValue::String("src".to_string()), &OptionValue::String("tests".to_string())]
+//! );
+//! assert_eq!(parsed.args, vec!["build"]);
+//! assert!(parsed.invalid.is_empty());
+//! ```
+
+use crate::number::number_parts;
+use crate::VecKeylist;
+use std::ffi::OsString;
+use std::iter::Peekable;
+
+/// The type of a switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// `--flag` is true, `--no-flag` is false
+    Boolean,
+    /// counts how often the switch is given, stored as an integer
+    Count,
+    Integer,
+    Float,
+    String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionValue {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Switch {
+    kind: Kind,
+    keep: bool,
+}
+
+/// The switches and aliases that are accepted, any other switch is invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spec {
+    switches: VecKeylist<String, Switch>,
+    aliases: VecKeylist<char, String>,
+}
+
+/// The result of [`Spec::parse`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Parsed {
+    pub options: VecKeylist<String, OptionValue>,
+    /// arguments that are not switches, and everything after `--`
+    pub args: Vec<OsString>,
+    /// switches as written on the command line, with their value if there was one
+    pub invalid: Vec<(String, Option<String>)>,
+}
+
+impl Default for Spec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Spec {
+    pub fn new() -> Self {
+        Spec {
+            switches: VecKeylist::new(),
+            aliases: VecKeylist::new(),
+        }
+    }
+
+    /// Adds a switch, when it is given more than once the last value wins.
+    pub fn switch(mut self, name: &str, kind: Kind) -> Self {
+        self.switches
+            .push(name.to_string(), Switch { kind, keep: false });
+        self
+    }
+
+    /// Adds a switch that keeps every value when it is given more than once.
+    pub fn keep(mut self, name: &str, kind: Kind) -> Self {
+        self.switches
+            .push(name.to_string(), Switch { kind, keep: true });
+        self
+    }
+
+    /// Adds a single letter alias, `-v` for `verbose`.
+    pub fn alias(mut self, alias: char, name: &str) -> Self {
+        self.aliases.push(alias, name.to_string());
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<Switch> {
+        self.switches
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, switch)| *switch)
+    }
+
+    /// Parses the arguments, without the program name: `spec.parse(std::env::args_os().skip(1))`.
+    pub fn parse<I, T>(&self, args: I) -> Parsed
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        let mut args = args.into_iter().map(Into::into).peekable();
+        let mut parsed = Parsed::default();
+
+        while let Some(arg) = args.next() {
+            let text = match arg.to_str() {
+                Some(text) => text,
+                None => {
+                    parsed.args.push(arg);
+                    continue;
+                }
+            };
+
+            if text == "--" {
+                parsed.args.extend(args);
+                break;
+            } else if let Some(body) = text.strip_prefix("--") {
+                self.long(body, &mut args, &mut parsed);
+            } else if text.len() > 1 && text.starts_with('-') && !is_number(text) {
+                self.short(&text[1..], &mut args, &mut parsed);
+            } else {
+                parsed.args.push(arg);
+            }
+        }
+
+        parsed
+    }
+
+    fn long<I>(&self, body: &str, args: &mut Peekable<I>, parsed: &mut Parsed)
+    where
+        I: Iterator<Item = OsString>,
+    {
+        let (name, value) = match body.find('=') {
+            Some(pos) => (&body[..pos], Some(&body[pos + 1..])),
+            None => (body, None),
+        };
+        let written = format!("--{}", name);
+        if name.contains('_') {
+            parsed.invalid.push((written, value.map(String::from)));
+            return;
+        }
+
+        let key = name.replace('-', "_");
+        if let Some(switch) = self.get(&key) {
+            return handle(key, switch, written, value, args, parsed);
+        }
+
+        match key.strip_prefix("no_") {
+            Some(negated) if value.is_none() => match self.get(negated) {
+                Some(switch) if switch.kind == Kind::Boolean => store(
+                    parsed,
+                    negated.to_string(),
+                    switch,
+                    OptionValue::Boolean(false),
+                ),
+                _ => parsed.invalid.push((written, None)),
+            },
+            _ => parsed.invalid.push((written, value.map(String::from))),
+        }
+    }
+
+    fn short<I>(&self, body: &str, args: &mut Peekable<I>, parsed: &mut Parsed)
+    where
+        I: Iterator<Item = OsString>,
+    {
+        let mut chars = body.chars();
+        let alias = match (chars.next(), chars.next()) {
+            (Some(c), None) => self.aliases.iter().find(|(k, _)| *k == c),
+            _ => None,
+        };
+        let written = format!("-{}", body);
+        match alias.and_then(|(_, name)| Some((name, self.get(name)?))) {
+            Some((name, switch)) => handle(name.clone(), switch, written, None, args, parsed),
+            None => parsed.invalid.push((written, None)),
+        }
+    }
+}
+
+/// Only plain numbers like `-5`, `-0.5` or `-1e3`, so switches such as `-inf` or `-nan` stay switches.
+fn is_number(text: &str) -> bool {
+    number_parts(text).is_some()
+}
+
+/// Floats have to be finite, `inf` and `nan` are not values just like `-inf` and `-nan` are not.
+fn parse_value(kind: Kind, value: &str) -> Option<OptionValue> {
+    match kind {
+        Kind::Integer => value.parse().ok().map(OptionValue::Integer),
+        Kind::Float => value
+            .parse()
+            .ok()
+            .filter(|x: &f64| x.is_finite())
+            .map(OptionValue::Float),
+        _ => Some(OptionValue::String(value.to_string())),
+    }
+}
+
+/// Takes the next argument as the value, unless it looks like a switch.
+fn next_value<I>(kind: Kind, args: &mut Peekable<I>) -> Option<String>
+where
+    I: Iterator<Item = OsString>,
+{
+    let text = args.peek()?.to_str()?;
+    let takes = match kind {
+        Kind::Integer | Kind::Float => !text.starts_with('-') || is_number(text),
+        _ => !text.starts_with('-') || text == "-",
+    };
+    if takes {
+        let text = text.to_string();
+        args.next();
+        Some(text)
+    } else {
+        None
+    }
+}
+
+fn handle<I>(
+    key: String,
+    switch: Switch,
+    written: String,
+    value: Option<&str>,
+    args: &mut Peekable<I>,
+    parsed: &mut Parsed,
+) where
+    I: Iterator<Item = OsString>,
+{
+    match switch.kind {
+        Kind::Boolean => match value {
+            None | Some("true") => store(parsed, key, switch, OptionValue::Boolean(true)),
+            Some("false") => store(parsed, key, switch, OptionValue::Boolean(false)),
+            Some(value) => parsed.invalid.push((written, Some(value.to_string()))),
+        },
+        Kind::Count => match value {
+            None => store(parsed, key, switch, OptionValue::Integer(1)),
+            Some(value) => parsed.invalid.push((written, Some(value.to_string()))),
+        },
+        kind => {
+            let value = match value {
+                Some(value) => value.to_string(),
+                None => match next_value(kind, args) {
+                    Some(value) => value,
+                    None => return parsed.invalid.push((written, None)),
+                },
+            };
+            match parse_value(kind, &value) {
+                Some(value) => store(parsed, key, switch, value),
+                None => parsed.invalid.push((written, Some(value))),
+            }
+        }
+    }
+}
+
+fn store(parsed: &mut Parsed, key: String, switch: Switch, value: OptionValue) {
+    let options = &mut parsed.options;
+    if switch.kind == Kind::Count {
+        match options.get_mut(&key) {
+            Some(OptionValue::Integer(count)) => *count += 1,
+            _ => options.push(key, value),
+        }
+    } else if switch.keep {
+        options.push(key, value);
+    } else {
+        options.0.retain(|(k, _)| k != &key);
+        options.push(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Kind, OptionValue, Parsed, Spec};
+    use crate::VecKeylist;
+    use std::ffi::OsString;
+
+    fn spec() -> Spec {
+        Spec::new()
+            .switch("verbose", Kind::Count)
+            .switch("debug", Kind::Boolean)
+            .switch("name", Kind::String)
+            .switch("jobs", Kind::Integer)
+            .switch("ratio", Kind::Float)
+            .keep("tag", Kind::String)
+            .switch("dry_run", Kind::Boolean)
+            .alias('v', "verbose")
+            .alias('j', "jobs")
+    }
+
+    fn options(pairs: Vec<(&str, OptionValue)>) -> VecKeylist<String, OptionValue> {
+        pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+    }
+
+    fn invalid(switch: &str, value: Option<&str>) -> (String, Option<String>) {
+        (switch.to_string(), value.map(String::from))
+    }
+
+    #[test]
+    fn types() {
+        let parsed = spec().parse(vec![
+            "--debug",
+            "--name",
+            "app",
+            "--jobs=4",
+            "--ratio",
+            "-0.5",
+            "--dry-run",
+        ]);
+
+        let expected = options(vec![
+            ("debug", OptionValue::Boolean(true)),
+            ("name", OptionValue::String("app".to_string())),
+            ("jobs", OptionValue::Integer(4)),
+            ("ratio", OptionValue::Float(-0.5)),
+            ("dry_run", OptionValue::Boolean(true)),
+        ]);
+        assert_eq!(parsed.options, expected);
+        assert!(parsed.args.is_empty());
+        assert!(parsed.invalid.is_empty());
+    }
+
+    #[test]
+    fn booleans() {
+        let parsed = spec().parse(vec!["--debug=false", "--no-dry-run", "--no-name"]);
+
+        let expected = options(vec![
+            ("debug", OptionValue::Boolean(false)),
+            ("dry_run", OptionValue::Boolean(false)),
+        ]);
+        assert_eq!(parsed.options, expected);
+        assert_eq!(parsed.invalid, vec![invalid("--no-name", None)]);
+    }
+
+    #[test]
+    fn repeated() {
+        let parsed = spec().parse(vec![
+            "--name",
+            "a",
+            "-v",
+            "--tag",
+            "x",
+            "--name",
+            "b",
+            "--verbose",
+            "--tag=y",
+            "-v",
+        ]);
+
+        let expected = options(vec![
+            ("verbose", OptionValue::Integer(3)),
+            ("tag", OptionValue::String("x".to_string())),
+            ("name", OptionValue::String("b".to_string())),
+            ("tag", OptionValue::String("y".to_string())),
+        ]);
+        assert_eq!(parsed.options, expected);
+    }
+
+    #[test]
+    fn args_and_terminator() {
+        let parsed = spec().parse(vec!["build", "-j", "2", "-", "-5", "--", "--debug", "-v"]);
+
+        assert_eq!(
+            parsed.options,
+            options(vec![("jobs", OptionValue::Integer(2))])
+        );
+        assert_eq!(parsed.args, vec!["build", "-", "-5", "--debug", "-v"]);
+    }
+
+    #[test]
+    fn negative_numbers() {
+        let parsed = spec().parse(vec!["-1e3", "-0.5", "-inf", "-nan", "-infinity"]);
+
+        assert_eq!(parsed.args, vec!["-1e3", "-0.5"]);
+        assert_eq!(parsed.invalid.len(), 3);
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let parsed = spec().parse(vec![
+            "--ratio",
+            "inf",
+            "--ratio",
+            "nan",
+            "--ratio=infinity",
+            "--ratio",
+            "1e999",
+        ]);
+
+        assert_eq!(
+            parsed.invalid,
+            vec![
+                invalid("--ratio", Some("inf")),
+                invalid("--ratio", Some("nan")),
+                invalid("--ratio", Some("infinity")),
+                invalid("--ratio", Some("1e999")),
+            ]
+        );
+        assert!(parsed.options.is_empty());
+    }
+
+    #[test]
+    fn invalid_switches() {
+        let parsed = spec().parse(vec![
+            "--unknown",
+            "--other=1",
+            "-x",
+            "--jobs=many",
+            "--dry_run",
+            "--verbose=2",
+            "--name",
+            "--debug=maybe",
+            "--ratio",
+        ]);
+
+        assert_eq!(
+            parsed.invalid,
+            vec![
+                invalid("--unknown", None),
+                invalid("--other", Some("1")),
+                invalid("-x", None),
+                invalid("--jobs", Some("many")),
+                invalid("--dry_run", None),
+                invalid("--verbose", Some("2")),
+                invalid("--name", None),
+                invalid("--debug", Some("maybe")),
+                invalid("--ratio", None),
+            ]
+        );
+        assert!(parsed.options.is_empty());
+        assert!(parsed.args.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_unicode_args() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let arg = OsString::from_vec(vec![b'-', b'-', 0xff]);
+        let parsed = spec().parse(vec![arg.clone(), OsString::from("-v")]);
+
+        assert_eq!(
+            parsed,
+            Parsed {
+                options: options(vec![("verbose", OptionValue::Integer(1))]),
+                args: vec![arg],
+                invalid: Vec::new(),
+            }
+        );
+    }
+}
@@ -0,0 +1,622 @@
+//! A keylist with a perfect hash index that is built at compile time by the `phf_keylist!` macro.
+//!
+//! Meant for large static lookup tables with `&'static str` keys. The index is computed while compiling, so
+//! there is nothing to set up at runtime, a lookup hashes the key once and compares it with one pair. Duplicate keys
+//! are kept, `get_all` returns them in the order of the literal list, and iterating goes over the pairs in that order.
+//! ```
+//! use keylist::{phf_keylist, PhfKeylist};
+//!
+//! static MIME: PhfKeylist<&str> = phf_keylist! {
+//!     "html" => "text/html",
+//!     "js" => "text/javascript",
+//!     "js" => "application/javascript",
+//!     "png" => "image/png",
+//! };
+//!
+//! assert_eq!(MIME.get("html"), Some(&"text/html"));
+//! assert_eq!(MIME.get_all("js"), vec![&"text/javascript", &"application/javascript"]);
+//! assert_eq!(MIME.get("gif"), None);
+//! assert_eq!(MIME.keys().collect::<Vec<_>>(), vec![&"html", &"js", &"js", &"png"]);
+//! ```
+//!
+//! The index uses hash and displace: the keys are spread over buckets of about `LAMBDA` keys, and every bucket gets
+//! the displacement that puts its keys in free slots, the biggest buckets first. There are a quarter more slots than
+//! keys and only a few displacements are tried per seed, so the search stays short, still a table of three thousand
+//! keys adds about four seconds to the build.
+
+use crate::{KeylistSlice, VecKeylist};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+use core::slice;
+
+/// Average number of keys per bucket.
+const LAMBDA: usize = 4;
+/// One spare slot for every `SPARE` distinct keys, a table with free slots is much quicker to fill.
+const SPARE: usize = 4;
+/// Values of `d2` to try for every `d1`, like rust-phf this bounds the search so a bad seed fails fast.
+const MAX_D2: u32 = 8;
+/// Seeds to try before giving up, in practice the first few work.
+const MAX_SEEDS: u64 = 1024;
+
+pub struct PhfKeylist<V: 'static> {
+    pairs: &'static [(&'static str, V)],
+    seed: u64,
+    displacements: &'static [(u32, u32)],
+    slots: &'static [(u32, u32)],
+    order: &'static [u32],
+}
+
+/// The index `phf_keylist!` builds, for `N` pairs with `D` distinct keys in `B` buckets and `S` slots.
+#[doc(hidden)]
+pub struct PhfIndex<const N: usize, const D: usize, const B: usize, const S: usize> {
+    seed: u64,
+    /// displacement of every bucket
+    displacements: [(u32, u32); B],
+    /// range in `order` of the key hashed to every slot, empty for a free slot
+    slots: [(u32, u32); S],
+    /// pair indices grouped by key
+    order: [u32; N],
+}
+
+/// Builds a `PhfKeylist` from a literal list of `key => value` pairs, the keys have to be `&'static str` literals.
+#[macro_export]
+macro_rules! phf_keylist {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        const KEYS: &[&str] = &[$($key),*];
+        const N: usize = KEYS.len();
+        const D: usize = $crate::phf_keylist::distinct::<N>(KEYS);
+        const B: usize = $crate::phf_keylist::buckets(D);
+        const S: usize = $crate::phf_keylist::slots(D);
+        // the search is bounded, but huge tables can still take a while
+        #[allow(long_running_const_eval)]
+        const INDEX: $crate::phf_keylist::PhfIndex<N, D, B, S> = $crate::phf_keylist::PhfIndex::build(KEYS);
+        $crate::PhfKeylist::from_index(&[$(($key, $value)),*], &INDEX)
+    }};
+}
+
+impl<V> PhfKeylist<V> {
+    #[doc(hidden)]
+    pub const fn from_index<const N: usize, const D: usize, const B: usize, const S: usize>(
+        pairs: &'static [(&'static str, V)],
+        index: &'static PhfIndex<N, D, B, S>,
+    ) -> Self {
+        PhfKeylist {
+            pairs,
+            seed: index.seed,
+            displacements: &index.displacements,
+            slots: &index.slots,
+            order: &index.order,
+        }
+    }
+
+    /// Indices of the pairs with the key.
+    fn positions(&self, key: &str) -> Option<&'static [u32]> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let (g, f1, f2) = hash(key, self.seed);
+        let (d1, d2) = self.displacements[g as usize % self.displacements.len()];
+        let (start, end) = self.slots[displace(f1, f2, d1, d2) as usize % self.slots.len()];
+        let positions = &self.order[start as usize..end as usize];
+        match positions.first() {
+            Some(i) if self.pairs[*i as usize].0 == key => Some(positions),
+            _ => None,
+        }
+    }
+
+    pub fn get_key_value(&self, key: &str) -> Option<&'static (&'static str, V)> {
+        let positions = self.positions(key)?;
+        Some(&self.pairs[positions[0] as usize])
+    }
+
+    pub fn get(&self, key: &str) -> Option<&'static V> {
+        let (_, v) = self.get_key_value(key)?;
+        Some(v)
+    }
+
+    /// get all values matching the key
+    pub fn get_all(&self, key: &str) -> Vec<&'static V> {
+        let pairs = self.pairs;
+        self.positions(key)
+            .unwrap_or_default()
+            .iter()
+            .map(|i| &pairs[*i as usize].1)
+            .collect()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.positions(key).is_some()
+    }
+
+    pub const fn as_slice(&self) -> &'static [(&'static str, V)] {
+        self.pairs
+    }
+
+    pub const fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Iterates in the order of the literal list.
+    pub fn iter(&self) -> slice::Iter<'static, (&'static str, V)> {
+        self.pairs.iter()
+    }
+}
+
+impl<V> Deref for PhfKeylist<V> {
+    type Target = KeylistSlice<&'static str, V>;
+
+    fn deref(&self) -> &KeylistSlice<&'static str, V> {
+        KeylistSlice::new(self.pairs)
+    }
+}
+
+impl<V> Clone for PhfKeylist<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for PhfKeylist<V> {}
+
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<V: fmt::Debug> fmt::Debug for PhfKeylist<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<V> IntoIterator for PhfKeylist<V> {
+    type Item = &'static (&'static str, V);
+    type IntoIter = slice::Iter<'static, (&'static str, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pairs.iter()
+    }
+}
+
+impl<V: Clone> From<PhfKeylist<V>> for VecKeylist<&'static str, V> {
+    fn from(list: PhfKeylist<V>) -> Self {
+        VecKeylist(list.pairs.to_vec())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<V: serde::Serialize> serde::Serialize for PhfKeylist<V> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct("PhfKeylist", self.pairs)
+    }
+}
+
+#[doc(hidden)]
+pub const fn distinct<const N: usize>(keys: &[&str]) -> usize {
+    let sorted = sort::<N>(keys);
+    let mut count = 0;
+    let mut i = 0;
+    while i < N {
+        if i == 0 || !str_eq(keys[sorted[i - 1] as usize], keys[sorted[i] as usize]) {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+#[doc(hidden)]
+pub const fn buckets(distinct: usize) -> usize {
+    if distinct == 0 {
+        1
+    } else {
+        distinct.div_ceil(LAMBDA)
+    }
+}
+
+#[doc(hidden)]
+pub const fn slots(distinct: usize) -> usize {
+    distinct + distinct / SPARE
+}
+
+impl<const N: usize, const D: usize, const B: usize, const S: usize> PhfIndex<N, D, B, S> {
+    /// Panics, so fails to compile, if `keys` does not have `N` keys of which `D` are distinct.
+    pub const fn build(keys: &[&str]) -> Self {
+        assert!(keys.len() == N && buckets(D) == B && slots(D) == S);
+
+        // sorting groups the pairs by key, keeping the order within a key
+        let order = sort::<N>(keys);
+        let mut firsts = [0u32; D];
+        let mut spans = [(0u32, 0u32); D];
+        let mut id = 0;
+        let mut i = 0;
+        while i < N {
+            if i > 0 && !str_eq(keys[order[i - 1] as usize], keys[order[i] as usize]) {
+                id += 1;
+                spans[id] = (i as u32, i as u32);
+            }
+            if spans[id].0 == spans[id].1 {
+                firsts[id] = order[i];
+            }
+            spans[id].1 += 1;
+            i += 1;
+        }
+
+        let mut seed = 0;
+        while seed < MAX_SEEDS {
+            if let Some((displacements, owners)) = place::<D, B, S>(keys, &firsts, seed) {
+                let mut slots = [(0u32, 0u32); S];
+                let mut slot = 0;
+                while slot < S {
+                    if owners[slot] != u32::MAX {
+                        slots[slot] = spans[owners[slot] as usize];
+                    }
+                    slot += 1;
+                }
+                return PhfIndex {
+                    seed,
+                    displacements,
+                    slots,
+                    order,
+                };
+            }
+            seed += 1;
+        }
+        panic!("no perfect hash found for the keys")
+    }
+}
+
+/// Finds a displacement for every bucket so every distinct key gets its own slot, returns the displacements and
+/// the key id in every slot, `u32::MAX` for a free one, or `None` if this seed does not work.
+#[allow(clippy::type_complexity)]
+const fn place<const D: usize, const B: usize, const S: usize>(
+    keys: &[&str],
+    firsts: &[u32; D],
+    seed: u64,
+) -> Option<([(u32, u32); B], [u32; S])> {
+    let mut hashes = [(0u32, 0u32, 0u32); D];
+    let mut sizes = [0u32; B];
+    let mut max = 0;
+    let mut id = 0;
+    while id < D {
+        let h = hash(keys[firsts[id] as usize], seed);
+        hashes[id] = h;
+        let bucket = h.0 as usize % B;
+        sizes[bucket] += 1;
+        if sizes[bucket] > max {
+            max = sizes[bucket];
+        }
+        id += 1;
+    }
+
+    // the key ids of every bucket, laid out bucket after bucket
+    let mut starts = [0u32; B];
+    let mut ends = [0u32; B];
+    let mut total = 0;
+    let mut bucket = 0;
+    while bucket < B {
+        starts[bucket] = total;
+        ends[bucket] = total;
+        total += sizes[bucket];
+        bucket += 1;
+    }
+    let mut members = [0u32; D];
+    id = 0;
+    while id < D {
+        let bucket = hashes[id].0 as usize % B;
+        members[ends[bucket] as usize] = id as u32;
+        ends[bucket] += 1;
+        id += 1;
+    }
+
+    let mut displacements = [(0u32, 0u32); B];
+    let mut owners = [u32::MAX; S];
+    // the attempt in which a slot was last tried, to catch keys of one bucket landing on the same slot
+    let mut tried = [0u32; S];
+    let mut attempt = 0;
+    let mut size = max;
+    while size > 0 {
+        bucket = 0;
+        while bucket < B {
+            if sizes[bucket] == size {
+                let mut placed = false;
+                let mut d1 = 0;
+                while !placed && d1 < S as u32 {
+                    let mut d2 = 0;
+                    while !placed && d2 < MAX_D2 {
+                        attempt += 1;
+                        let mut free = true;
+                        let mut member = starts[bucket];
+                        while free && member < ends[bucket] {
+                            let (_, f1, f2) = hashes[members[member as usize] as usize];
+                            let slot = displace(f1, f2, d1, d2) as usize % S;
+                            if owners[slot] != u32::MAX || tried[slot] == attempt {
+                                free = false;
+                            }
+                            tried[slot] = attempt;
+                            member += 1;
+                        }
+                        if free {
+                            member = starts[bucket];
+                            while member < ends[bucket] {
+                                let id = members[member as usize];
+                                let (_, f1, f2) = hashes[id as usize];
+                                owners[displace(f1, f2, d1, d2) as usize % S] = id;
+                                member += 1;
+                            }
+                            displacements[bucket] = (d1, d2);
+                            placed = true;
+                        }
+                        d2 += 1;
+                    }
+                    d1 += 1;
+                }
+                if !placed {
+                    return None;
+                }
+            }
+            bucket += 1;
+        }
+        size -= 1;
+    }
+    Some((displacements, owners))
+}
+
+/// Indices of the keys in sorted order, a stable bottom up merge sort.
+const fn sort<const N: usize>(keys: &[&str]) -> [u32; N] {
+    let mut sorted = [0u32; N];
+    let mut i = 0;
+    while i < N {
+        sorted[i] = i as u32;
+        i += 1;
+    }
+    let mut merged = [0u32; N];
+    let mut width = 1;
+    while width < N {
+        let mut low = 0;
+        while low < N {
+            let middle = if low + width < N { low + width } else { N };
+            let high = if low + 2 * width < N {
+                low + 2 * width
+            } else {
+                N
+            };
+            let (mut left, mut right, mut k) = (low, middle, low);
+            while k < high {
+                if left < middle
+                    && (right >= high
+                        || !str_lt(keys[sorted[right] as usize], keys[sorted[left] as usize]))
+                {
+                    merged[k] = sorted[left];
+                    left += 1;
+                } else {
+                    merged[k] = sorted[right];
+                    right += 1;
+                }
+                k += 1;
+            }
+            low = high;
+        }
+        sorted = merged;
+        width *= 2;
+    }
+    sorted
+}
+
+const fn str_lt(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+        i += 1;
+    }
+    a.len() < b.len()
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// FNV-1a followed by a finalizer, split in the bucket hash and the two slot hashes.
+const fn hash(key: &str, seed: u64) -> (u32, u32, u32) {
+    let bytes = key.as_bytes();
+    let mut h = 0xcbf2_9ce4_8422_2325 ^ seed;
+    let mut i = 0;
+    while i < bytes.len() {
+        h ^= bytes[i] as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        i += 1;
+    }
+    let a = mix(h);
+    let b = mix(a ^ 0x9e37_79b9_7f4a_7c15);
+    ((a >> 32) as u32, a as u32, b as u32)
+}
+
+const fn mix(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^ (h >> 33)
+}
+
+const fn displace(f1: u32, f2: u32, d1: u32, d2: u32) -> u32 {
+    d2.wrapping_add(f1.wrapping_mul(d1)).wrapping_add(f2)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PhfKeylist, VecKeylist};
+
+    static MIME: PhfKeylist<&str> = phf_keylist! {
+        "html" => "text/html",
+        "htm" => "text/html",
+        "js" => "text/javascript",
+        "json" => "application/json",
+        "js" => "application/javascript",
+        "png" => "image/png",
+        "jpg" => "image/jpeg",
+        "jpeg" => "image/jpeg",
+        "svg" => "image/svg+xml",
+        "txt" => "text/plain",
+        "js" => "application/x-javascript",
+        "css" => "text/css",
+        "wasm" => "application/wasm",
+        "" => "application/octet-stream",
+    };
+
+    #[test]
+    fn get() {
+        assert_eq!(MIME.get("html"), Some(&"text/html"));
+        assert_eq!(MIME.get("wasm"), Some(&"application/wasm"));
+        assert_eq!(MIME.get("js"), Some(&"text/javascript"));
+        assert_eq!(MIME.get(""), Some(&"application/octet-stream"));
+        assert_eq!(MIME.get_key_value("svg"), Some(&("svg", "image/svg+xml")));
+        assert_eq!(MIME.get("gif"), None);
+        assert_eq!(MIME.get("jsx"), None);
+        assert!(MIME.contains_key("css"));
+        assert!(!MIME.contains_key("HTML"));
+    }
+
+    #[test]
+    fn get_all() {
+        assert_eq!(
+            MIME.get_all("js"),
+            vec![
+                &"text/javascript",
+                &"application/javascript",
+                &"application/x-javascript"
+            ]
+        );
+        assert_eq!(MIME.get_all("png"), vec![&"image/png"]);
+        assert!(MIME.get_all("gif").is_empty());
+    }
+
+    #[test]
+    fn every_key() {
+        for (key, _) in MIME.iter() {
+            let expected: Vec<_> = MIME
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, v)| v)
+                .collect();
+            assert_eq!(MIME.get_all(key), expected);
+        }
+    }
+
+    #[test]
+    fn ordered_iter() {
+        let keylist = VecKeylist::from(MIME);
+
+        assert_eq!(MIME.len(), 14);
+        assert_eq!(keylist.as_slice(), MIME.as_slice());
+        assert_eq!(
+            MIME.keys().take(5).collect::<Vec<_>>(),
+            vec![&"html", &"htm", &"js", &"json", &"js"]
+        );
+        assert_eq!(MIME[2..4].get(&"json"), Some(&"application/json"));
+    }
+
+    #[test]
+    fn small() {
+        let empty: PhfKeylist<u8> = phf_keylist! {};
+        let single = phf_keylist! { "a" => 1 };
+        let same = phf_keylist! { "a" => 1, "a" => 2 };
+
+        assert!(empty.is_empty());
+        assert_eq!(empty.get("a"), None);
+        assert!(empty.get_all("a").is_empty());
+        assert_eq!(single.get("a"), Some(&1));
+        assert_eq!(single.get("b"), None);
+        assert_eq!(same.get_all("a"), vec![&1, &2]);
+        assert_eq!(format!("{:?}", same), r#"[("a", 1), ("a", 2)]"#);
+    }
+
+    /// Every combination of a prefix and a suffix, `concat!(prefix, suffix) => concat!(suffix, prefix)`.
+    macro_rules! product {
+        ([$($prefix:literal)*] $suffixes:tt) => {
+            product!(@pairs [] [$($prefix)*] $suffixes)
+        };
+        (@pairs [$($pairs:tt)*] [] $suffixes:tt) => {
+            phf_keylist! { $($pairs)* }
+        };
+        (@pairs [$($pairs:tt)*] [$prefix:literal $($rest:literal)*] [$($suffix:literal)*]) => {
+            product!(
+                @pairs
+                [$($pairs)* $(concat!($prefix, $suffix) => concat!($suffix, $prefix),)*]
+                [$($rest)*]
+                [$($suffix)*]
+            )
+        };
+    }
+
+    static LARGE: PhfKeylist<&str> = product!(
+        [
+            "ba" "be" "bi" "bo" "bu" "ca" "ce" "ci" "co" "cu" "da" "de" "di" "do" "du"
+            "fa" "fe" "fi" "fo" "fu" "ga" "ge" "gi" "go" "gu" "ha" "he" "hi" "ho" "hu"
+            "ja" "je" "ji" "jo" "ju" "ka" "ke" "ki" "ko" "ku" "la" "le" "li" "lo" "lu"
+            "ma" "me" "mi" "mo" "mu" "na" "ne" "ni" "no" "nu" "pa" "pe" "pi" "po" "pu"
+        ]
+        [
+            "-0" "-1" "-2" "-3" "-4" "-5" "-6" "-7" "-8" "-9" "-10" "-11" "-12"
+            "-13" "-14" "-15" "-16" "-17" "-18" "-19" "-20" "-21" "-22" "-23" "-24" "-25"
+            "-26" "-27" "-28" "-29" "-30" "-31" "-32" "-33" "-34" "-35" "-36" "-37" "-38"
+            "-39" "-40" "-41" "-42" "-43" "-44" "-45" "-46" "-47" "-48" "-49"
+        ]
+    );
+
+    #[test]
+    fn large() {
+        assert_eq!(LARGE.len(), 3000);
+        for (key, value) in LARGE.iter() {
+            assert_eq!(LARGE.get(key), Some(value));
+            assert_eq!(LARGE.get_all(key), vec![value]);
+            // the values are no keys, so some of them hash to free slots
+            assert!(!LARGE.contains_key(value));
+        }
+        assert_eq!(LARGE.get("ba-0"), Some(&"-0ba"));
+        assert_eq!(LARGE.get("ba-50"), None);
+        assert_eq!(LARGE.get("-0ba"), None);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use crate::PhfKeylist;
+    use serde_test::{assert_ser_tokens, Token};
+
+    #[test]
+    fn serde_ser() {
+        static KEYLIST: PhfKeylist<i32> = phf_keylist! { "oke" => 1 };
+
+        assert_ser_tokens(
+            &KEYLIST,
+            &[
+                Token::NewtypeStruct { name: "PhfKeylist" },
+                Token::Seq { len: Some(1) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
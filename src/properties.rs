@@ -0,0 +1,433 @@
+//! Java `.properties` files, keeping duplicate keys and their order.
+//!
+//! Files are ISO-8859-1 with `\uXXXX` escapes like `Properties.load(InputStream)`,
+//! or UTF-8 like `Properties.load(Reader)`.
+//! ```
+//! use keylist::properties::Encoding;
+//! use keylist::VecKeylist;
+//!
+//! let input = b"# settings\nname = caf\\u00e9\nurl: http://example.com\npath \\\n    /usr/lib\nname=bar\n";
+//!
+//! let keylist = VecKeylist::from_properties(&input[..], Encoding::Latin1).unwrap();
+//! assert_eq!(keylist.get_all(&"name".to_string()), vec!["café", "bar"]);
+//! assert_eq!(keylist.get(&"url".to_string()).unwrap(), "http://example.com");
+//! assert_eq!(keylist.get(&"path".to_string()).unwrap(), "/usr/lib");
+//!
+//! let mut output = Vec::new();
+//! keylist.write_properties(&mut output, Encoding::Latin1).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(output).unwrap(),
+//!     "name=caf\\u00E9\nurl=http\\://example.com\npath=/usr/lib\nname=bar\n"
+//! );
+//! ```
+
+use crate::VecKeylist;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// ISO-8859-1, other characters are written as `\uXXXX`
+    Latin1,
+    Utf8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidUtf8,
+    /// a `\u` not followed by four hex digits, or an unpaired surrogate
+    InvalidUnicodeEscape,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::InvalidUtf8 => f.write_str("invalid utf-8"),
+            ErrorKind::InvalidUnicodeEscape => f.write_str("invalid \\uXXXX escape"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// error with the 1-based line number
+    Parse {
+        line: usize,
+        kind: ErrorKind,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => e.fmt(f),
+            Error::Parse { line, kind } => write!(f, "line {}: {}", line, kind),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\x0C')
+}
+
+/// Splits on `\n`, `\r\n` and `\r`, with the 1-based line numbers.
+fn natural_lines(input: &str) -> Vec<(usize, &str)> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let bytes = input.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => {
+                lines.push(&input[start..i]);
+                start = i + 1;
+            }
+            b'\r' => {
+                lines.push(&input[start..i]);
+                if bytes.get(i + 1) == Some(&b'\n') {
+                    i += 1;
+                }
+                start = i + 1;
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    if start < bytes.len() {
+        lines.push(&input[start..]);
+    }
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| (i + 1, x))
+        .collect()
+}
+
+fn continues(line: &str) -> bool {
+    line.bytes().rev().take_while(|&b| b == b'\\').count() % 2 == 1
+}
+
+fn hex4(chars: &mut std::str::Chars) -> Option<u32> {
+    let mut value = 0;
+    for _ in 0..4 {
+        value = value * 16 + chars.next()?.to_digit(16)?;
+    }
+    Some(value)
+}
+
+fn unescape(input: &str, line: usize) -> Result<String, Error> {
+    let error = || Error::Parse {
+        line,
+        kind: ErrorKind::InvalidUnicodeEscape,
+    };
+    let mut buffer = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            buffer.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => buffer.push('\t'),
+            Some('n') => buffer.push('\n'),
+            Some('r') => buffer.push('\r'),
+            Some('f') => buffer.push('\x0C'),
+            Some('u') => {
+                let unit = hex4(&mut chars).ok_or_else(error)?;
+                let c = if (0xD800..0xDC00).contains(&unit) {
+                    let low = match (chars.next(), chars.next()) {
+                        (Some('\\'), Some('u')) => hex4(&mut chars).ok_or_else(error)?,
+                        _ => return Err(error()),
+                    };
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(error());
+                    }
+                    0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    unit
+                };
+                buffer.push(std::char::from_u32(c).ok_or_else(error)?);
+            }
+            Some(c) => buffer.push(c),
+            None => (),
+        }
+    }
+    Ok(buffer)
+}
+
+fn parse_entry(line: &str, number: usize) -> Result<(String, String), Error> {
+    let bytes = line.as_bytes();
+    let mut end = 0;
+    while end < bytes.len() {
+        match bytes[end] {
+            b'\\' => end += 1,
+            b'=' | b':' | b' ' | b'\t' | b'\x0C' => break,
+            _ => (),
+        }
+        end += 1;
+    }
+    let end = end.min(bytes.len());
+
+    let rest = line[end..].trim_start_matches(is_whitespace);
+    let rest = rest
+        .strip_prefix(['=', ':'])
+        .unwrap_or(rest)
+        .trim_start_matches(is_whitespace);
+
+    Ok((unescape(&line[..end], number)?, unescape(rest, number)?))
+}
+
+/// Parses an already decoded `.properties` file.
+pub fn parse(input: &str) -> Result<VecKeylist<String, String>, Error> {
+    let mut keylist = VecKeylist::new();
+    let mut lines = natural_lines(input).into_iter();
+
+    while let Some((number, line)) = lines.next() {
+        let line = line.trim_start_matches(is_whitespace);
+        if line.is_empty() || line.starts_with(['#', '!']) {
+            continue;
+        }
+
+        let mut logical = String::new();
+        let mut current = line;
+        while continues(current) {
+            logical.push_str(&current[..current.len() - 1]);
+            current = match lines.next() {
+                Some((_, next)) => next.trim_start_matches(is_whitespace),
+                None => "",
+            };
+        }
+        logical.push_str(current);
+
+        let (key, value) = parse_entry(&logical, number)?;
+        keylist.push(key, value);
+    }
+
+    Ok(keylist)
+}
+
+fn write_escaped(buffer: &mut String, input: &str, is_key: bool, encoding: Encoding) {
+    for (i, c) in input.chars().enumerate() {
+        match c {
+            ' ' if is_key || i == 0 => buffer.push_str("\\ "),
+            '\\' => buffer.push_str("\\\\"),
+            '\t' => buffer.push_str("\\t"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\x0C' => buffer.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                buffer.push('\\');
+                buffer.push(c);
+            }
+            c if c < ' ' || (encoding == Encoding::Latin1 && c > '~') => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    buffer.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+            c => buffer.push(c),
+        }
+    }
+}
+
+/// Formats the keylist as a `.properties` file, in `Latin1` mode the output is plain ASCII.
+pub fn to_string<K, V>(keylist: &VecKeylist<K, V>, encoding: Encoding) -> String
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut buffer = String::new();
+    for (k, v) in keylist.iter() {
+        write_escaped(&mut buffer, k.as_ref(), true, encoding);
+        buffer.push('=');
+        write_escaped(&mut buffer, v.as_ref(), false, encoding);
+        buffer.push('\n');
+    }
+    buffer
+}
+
+impl VecKeylist<String, String> {
+    pub fn from_properties<R: Read>(mut reader: R, encoding: Encoding) -> Result<Self, Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let input = match encoding {
+            Encoding::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+            Encoding::Utf8 => String::from_utf8(bytes).map_err(|e| {
+                let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+                Error::Parse {
+                    line: valid.iter().filter(|&&b| b == b'\n').count() + 1,
+                    kind: ErrorKind::InvalidUtf8,
+                }
+            })?,
+        };
+        parse(&input)
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> VecKeylist<K, V> {
+    pub fn write_properties<W: Write>(&self, mut writer: W, encoding: Encoding) -> io::Result<()> {
+        writer.write_all(to_string(self, encoding).as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, to_string, Encoding, Error, ErrorKind};
+    use crate::VecKeylist;
+
+    fn keylist(pairs: Vec<(&str, &str)>) -> VecKeylist<String, String> {
+        pairs
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn separators() {
+        let input = "a=1\nb:2\nc 3\nd\t = \t4\ne\nf=\n=g\nh::\n  i = x = y\n";
+        let expected = keylist(vec![
+            ("a", "1"),
+            ("b", "2"),
+            ("c", "3"),
+            ("d", "4"),
+            ("e", ""),
+            ("f", ""),
+            ("", "g"),
+            ("h", ":"),
+            ("i", "x = y"),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn comments_and_duplicates() {
+        let input =
+            "# comment\n! other\n\n   # indented \\\nkey=1\nkey=2 # not a comment\r\nkey=3\rlast=x";
+        let expected = keylist(vec![
+            ("key", "1"),
+            ("key", "2 # not a comment"),
+            ("key", "3"),
+            ("last", "x"),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn continuations() {
+        let input = "fruits apple, banana, \\\n    pear, \\\n\tcherry\nescaped=a\\\\\nnext=b\\\n";
+        let expected = keylist(vec![
+            ("fruits", "apple, banana, pear, cherry"),
+            ("escaped", "a\\"),
+            ("next", "b"),
+        ]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn escapes() {
+        let input = r"key\ with\ spaces\=and\:=tab\there\nnewline é😀 \q\\";
+        let expected = keylist(vec![("key with spaces=and:", "tab\there\nnewline é😀 q\\")]);
+
+        assert_eq!(parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_escapes() {
+        for input in &["a=\\u12", "a=\\uzzzz", "\n\na=\\uD83D", "a=\\uDE00"] {
+            assert!(matches!(
+                parse(input),
+                Err(Error::Parse {
+                    kind: ErrorKind::InvalidUnicodeEscape,
+                    ..
+                })
+            ));
+        }
+        assert_eq!(
+            parse("\n\na=\\uD83D").unwrap_err().to_string(),
+            "line 3: invalid \\uXXXX escape"
+        );
+    }
+
+    #[test]
+    fn encodings() {
+        let latin1 = b"name=caf\xe9\n";
+        let utf8 = "name=café\n".as_bytes();
+        let expected = keylist(vec![("name", "café")]);
+
+        assert_eq!(
+            VecKeylist::from_properties(&latin1[..], Encoding::Latin1).unwrap(),
+            expected
+        );
+        assert_eq!(
+            VecKeylist::from_properties(utf8, Encoding::Utf8).unwrap(),
+            expected
+        );
+        assert!(matches!(
+            VecKeylist::from_properties(&b"a=1\nname=caf\xe9\n"[..], Encoding::Utf8),
+            Err(Error::Parse {
+                line: 2,
+                kind: ErrorKind::InvalidUtf8
+            })
+        ));
+    }
+
+    #[test]
+    fn writing() {
+        let input = keylist(vec![
+            ("key with space", " leading and trailing "),
+            ("a=b:c", "#!\\"),
+            ("lines", "a\nb\tc\r\x0C\x01"),
+            ("é", "😀"),
+        ]);
+
+        assert_eq!(
+            to_string(&input, Encoding::Latin1),
+            "key\\ with\\ space=\\ leading and trailing \n\
+             a\\=b\\:c=\\#\\!\\\\\n\
+             lines=a\\nb\\tc\\r\\f\\u0001\n\
+             \\u00E9=\\uD83D\\uDE00\n"
+        );
+        assert_eq!(
+            to_string(&input, Encoding::Utf8).lines().last().unwrap(),
+            "é=😀"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = keylist(vec![
+            ("a", "1"),
+            (" key ", " value "),
+            ("a", "é😀\n\\"),
+            ("", ""),
+        ]);
+
+        for &encoding in &[Encoding::Latin1, Encoding::Utf8] {
+            let mut output = Vec::new();
+            input.write_properties(&mut output, encoding).unwrap();
+
+            assert_eq!(
+                VecKeylist::from_properties(&output[..], encoding).unwrap(),
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! URL query string and `application/x-www-form-urlencoded` codec.
+//!
+//! Parsing follows the [URL standard](https://url.spec.whatwg.org/#urlencoded-parsing):
+//! `+` is a space, invalid percent escapes are kept as is and invalid UTF-8 is replaced.
+//! ```
+//! use keylist::VecKeylist;
+//!
+//! let keylist = VecKeylist::from_query_str("?tag=a&name=J%C3%BCrgen+M&tag=b&debug");
+//!
+//! assert_eq!(keylist.get_all(&"tag".to_string()), vec!["a", "b"]);
+//! assert_eq!(keylist.get(&"name".to_string()).unwrap(), "Jürgen M");
+//! assert_eq!(keylist.get(&"debug".to_string()).unwrap(), "");
+//!
+//! assert_eq!(keylist.to_query_string(), "tag=a&name=J%C3%BCrgen+M&tag=b&debug=");
+//! ```
+
+use crate::{HashKeylist, VecKeylist};
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+/// Decodes `+` and percent escapes, borrows the input if there is nothing to decode.
+pub fn decode(input: &str) -> Cow<'_, str> {
+    if !input.contains(['+', '%']) {
+        return Cow::Borrowed(input);
+    }
+
+    let bytes = input.as_bytes();
+    let mut buffer = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => buffer.push(b' '),
+            b'%' => match (
+                bytes.get(i + 1).and_then(|&x| hex_value(x)),
+                bytes.get(i + 2).and_then(|&x| hex_value(x)),
+            ) {
+                (Some(high), Some(low)) => {
+                    buffer.push(high << 4 | low);
+                    i += 2;
+                }
+                _ => buffer.push(b'%'),
+            },
+            byte => buffer.push(byte),
+        }
+        i += 1;
+    }
+
+    match String::from_utf8(buffer) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(e) => Cow::Owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+    }
+}
+
+/// Encodes everything except ASCII alphanumerics and `*-._`, spaces become `+`.
+pub fn encode(input: &str) -> Cow<'_, str> {
+    if input.bytes().all(is_unreserved) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut buffer = String::with_capacity(input.len() * 3);
+    for byte in input.bytes() {
+        match byte {
+            b' ' => buffer.push('+'),
+            byte if is_unreserved(byte) => buffer.push(byte as char),
+            byte => {
+                buffer.push('%');
+                buffer.push(HEX[(byte >> 4) as usize] as char);
+                buffer.push(HEX[(byte & 0xF) as usize] as char);
+            }
+        }
+    }
+    Cow::Owned(buffer)
+}
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'*' | b'-' | b'.' | b'_')
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|x| x as u8)
+}
+
+/// Splits the query in undecoded key value pairs, a key without `=` gets an empty value.
+fn split(query: &str) -> impl Iterator<Item = (&str, &str)> {
+    query
+        .strip_prefix('?')
+        .unwrap_or(query)
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.find('=') {
+            Some(pos) => (&pair[..pos], &pair[pos + 1..]),
+            None => (pair, ""),
+        })
+}
+
+fn write_query<'a, K, V, I>(pairs: I) -> String
+where
+    K: AsRef<str> + 'a,
+    V: AsRef<str> + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    let mut buffer = String::new();
+    for (k, v) in pairs {
+        if !buffer.is_empty() {
+            buffer.push('&');
+        }
+        buffer.push_str(&encode(k.as_ref()));
+        buffer.push('=');
+        buffer.push_str(&encode(v.as_ref()));
+    }
+    buffer
+}
+
+impl VecKeylist<String, String> {
+    pub fn from_query_str(query: &str) -> Self {
+        split(query)
+            .map(|(k, v)| (decode(k).into_owned(), decode(v).into_owned()))
+            .collect()
+    }
+}
+
+impl<'a> VecKeylist<Cow<'a, str>, Cow<'a, str>> {
+    /// Same as `from_query_str`, but only allocates for keys and values that need decoding.
+    pub fn from_query_str_borrowed(query: &'a str) -> Self {
+        split(query).map(|(k, v)| (decode(k), decode(v))).collect()
+    }
+}
+
+impl<K: AsRef<str>, V: AsRef<str>> VecKeylist<K, V> {
+    pub fn to_query_string(&self) -> String {
+        write_query(self.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl HashKeylist<String, String, RandomState> {
+    pub fn from_query_str(query: &str) -> Self {
+        split(query)
+            .map(|(k, v)| (decode(k).into_owned(), decode(v).into_owned()))
+            .collect()
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: AsRef<str> + Hash + Eq,
+    V: AsRef<str> + Eq,
+    S: BuildHasher,
+{
+    pub fn to_query_string(&self) -> String {
+        write_query(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+    use crate::{HashKeylist, VecKeylist};
+    use std::borrow::Cow;
+
+    fn keylist(pairs: Vec<(&str, &str)>) -> VecKeylist<String, String> {
+        pairs
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn from_query_str() {
+        let expected = keylist(vec![("a", "1"), ("b", "2"), ("a", "3")]);
+
+        assert_eq!(VecKeylist::from_query_str("a=1&b=2&a=3"), expected);
+        assert_eq!(VecKeylist::from_query_str("?a=1&b=2&a=3"), expected);
+    }
+
+    #[test]
+    fn empty_values_and_keys() {
+        let expected = keylist(vec![("flag", ""), ("empty", ""), ("", "x"), ("a", "b=c")]);
+
+        assert_eq!(
+            VecKeylist::from_query_str("flag&empty=&&=x&a=b=c&"),
+            expected
+        );
+        assert_eq!(VecKeylist::from_query_str(""), VecKeylist::new());
+        assert_eq!(VecKeylist::from_query_str("?"), VecKeylist::new());
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(decode("a+b%20c"), "a b c");
+        assert_eq!(decode("%C3%A9%e2%82%ac"), "é€");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%zz%4"), "%zz%4");
+        assert_eq!(decode("%2B"), "+");
+        assert_eq!(decode("%FFa"), "\u{FFFD}a");
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(encode("a b+c"), "a+b%2Bc");
+        assert_eq!(encode("é&="), "%C3%A9%26%3D");
+        assert_eq!(encode("*-._~"), "*-._%7E");
+        assert!(matches!(encode("plain_Text.1"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn to_query_string() {
+        let input = keylist(vec![
+            ("q", "rust lang"),
+            ("tag", "a&b"),
+            ("tag", ""),
+            ("é", "1"),
+        ]);
+
+        assert_eq!(
+            input.to_query_string(),
+            "q=rust+lang&tag=a%26b&tag=&%C3%A9=1"
+        );
+        assert_eq!(VecKeylist::from_query_str(&input.to_query_string()), input);
+    }
+
+    #[test]
+    fn to_query_string_str() {
+        let input = VecKeylist(vec![("a", "1"), ("b", "x y")]);
+
+        assert_eq!(input.to_query_string(), "a=1&b=x+y");
+        assert_eq!(VecKeylist::<&str, &str>::new().to_query_string(), "");
+    }
+
+    #[test]
+    fn borrowed() {
+        let query = String::from("a=1&b=x+y&c%3D=3");
+        let keylist = VecKeylist::from_query_str_borrowed(&query);
+
+        let pairs: Vec<_> = keylist.iter().collect();
+        assert!(matches!(pairs[0], (Cow::Borrowed("a"), Cow::Borrowed("1"))));
+        assert!(matches!(pairs[1], (Cow::Borrowed("b"), Cow::Owned(_))));
+        assert!(matches!(pairs[2], (Cow::Owned(_), Cow::Borrowed("3"))));
+        assert_eq!(keylist.get(&Cow::Borrowed("b")).unwrap(), "x y");
+        assert_eq!(keylist.get(&Cow::Borrowed("c=")).unwrap(), "3");
+        assert_eq!(keylist.to_query_string(), "a=1&b=x+y&c%3D=3");
+    }
+
+    #[test]
+    fn hash_keylist() {
+        let keylist = HashKeylist::from_query_str("a=1&b=x+y&a=3");
+
+        assert_eq!(
+            Some(&vec!["1".to_string(), "3".to_string()]),
+            keylist.get_all(&"a".to_string())
+        );
+        assert_eq!(keylist.to_query_string(), "a=1&b=x+y&a=3");
+    }
+}
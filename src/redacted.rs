@@ -0,0 +1,302 @@
+//! A view that masks secret values when a keylist is logged or serialized.
+//!
+//! Keys, their order and the number of occurrences stay visible, only the values of the
+//! matching keys are replaced by `[REDACTED]`.
+//! ```
+//! use keylist::VecKeylist;
+//!
+//! let keylist = VecKeylist(vec![("user", "alice"), ("password", "hunter2"), ("token", "abc")]);
+//! let redacted = keylist.redact_keys(vec!["password", "token"]);
+//!
+//! assert_eq!(
+//!     format!("{:?}", redacted),
+//!     r#"[("user", "alice"), ("password", [REDACTED]), ("token", [REDACTED])]"#
+//! );
+//! assert_eq!(
+//!     redacted.to_string(),
+//!     r#"[user: "alice", password: "[REDACTED]", token: "[REDACTED]"]"#
+//! );
+//!
+//! let redacted = keylist.redact(|key| key.ends_with("word"));
+//! assert_eq!(redacted.to_string(), r#"[user: "alice", password: "[REDACTED]", token: "abc"]"#);
+//! ```
+
+use crate::display::{DisplayWith, Style};
+use crate::{HashKeylist, VecKeylist};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+pub const MASK: &str = "[REDACTED]";
+
+/// A value that is either shown or masked.
+struct Masked<'a, V>(Option<&'a V>);
+
+impl<'a, V: fmt::Debug> fmt::Debug for Masked<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str(MASK),
+        }
+    }
+}
+
+impl<'a, V: fmt::Display> fmt::Display for Masked<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str(MASK),
+        }
+    }
+}
+
+/// Returned by `redact` and `redact_keys`.
+pub struct Redacted<'a, K, V> {
+    pairs: Vec<(&'a K, &'a V)>,
+    is_secret: Box<dyn Fn(&K) -> bool + 'a>,
+    style: Style,
+    /// newtype name of the keylist it was made from, so it serializes the same
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    name: &'static str,
+}
+
+impl<'a, K, V> Redacted<'a, K, V> {
+    fn new<F>(name: &'static str, pairs: Vec<(&'a K, &'a V)>, is_secret: F) -> Self
+    where
+        F: Fn(&K) -> bool + 'a,
+    {
+        Redacted {
+            pairs,
+            is_secret: Box::new(is_secret),
+            style: Style::Elixir,
+            name,
+        }
+    }
+
+    /// The style used by `Display`, `Style::Elixir` by default.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn masked(&self) -> impl Iterator<Item = (&'a K, Masked<'a, V>)> + '_ {
+        self.pairs.iter().map(move |&(k, v)| {
+            if (self.is_secret)(k) {
+                (k, Masked(None))
+            } else {
+                (k, Masked(Some(v)))
+            }
+        })
+    }
+}
+
+impl<'a, K: fmt::Debug, V: fmt::Debug> fmt::Debug for Redacted<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.masked()).finish()
+    }
+}
+
+impl<'a, K: fmt::Display, V: fmt::Display> fmt::Display for Redacted<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let masked: Vec<_> = self.masked().collect();
+        let pairs = masked.iter().map(|(k, v)| (*k, v)).collect();
+        fmt::Display::fmt(&DisplayWith::new(pairs, self.style), f)
+    }
+}
+
+impl<K, V> VecKeylist<K, V> {
+    /// Masks the values of every key for which the predicate returns true.
+    pub fn redact<'a, F>(&'a self, is_secret: F) -> Redacted<'a, K, V>
+    where
+        F: Fn(&K) -> bool + 'a,
+    {
+        Redacted::new(
+            "VecKeylist",
+            self.iter().map(|(k, v)| (k, v)).collect(),
+            is_secret,
+        )
+    }
+}
+
+impl<K: PartialEq, V> VecKeylist<K, V> {
+    /// Masks the values of the given keys.
+    pub fn redact_keys<'a, I>(&'a self, keys: I) -> Redacted<'a, K, V>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        self.redact(move |key| keys.contains(key))
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    /// Masks the values of every key for which the predicate returns true.
+    pub fn redact<'a, F>(&'a self, is_secret: F) -> Redacted<'a, K, V>
+    where
+        F: Fn(&K) -> bool + 'a,
+    {
+        Redacted::new("HashKeylist", self.iter().collect(), is_secret)
+    }
+
+    /// Masks the values of the given keys.
+    pub fn redact_keys<'a, I>(&'a self, keys: I) -> Redacted<'a, K, V>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        self.redact(move |key| keys.contains(key))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::{Masked, Redacted, MASK};
+    use serde::ser::{Serialize, Serializer};
+
+    impl<'a, V: Serialize> Serialize for Masked<'a, V> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match self.0 {
+                Some(value) => value.serialize(serializer),
+                None => serializer.serialize_str(MASK),
+            }
+        }
+    }
+
+    /// Serializes like the keylist it was made from, as a newtype struct around the sequence of pairs.
+    impl<'a, K: Serialize, V: Serialize> Serialize for Redacted<'a, K, V> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_newtype_struct(self.name, &self.masked().collect::<Vec<_>>())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::display::Style;
+    use crate::{HashKeylist, VecKeylist};
+
+    fn keylist() -> VecKeylist<String, &'static str> {
+        VecKeylist(vec![
+            ("user".to_string(), "alice"),
+            ("token".to_string(), "a"),
+            ("host".to_string(), "example.com"),
+            ("token".to_string(), "b"),
+        ])
+    }
+
+    #[test]
+    fn debug() {
+        let keylist = keylist();
+        let redacted = keylist.redact_keys(vec!["token".to_string()]);
+
+        assert_eq!(
+            format!("{:?}", redacted),
+            r#"[("user", "alice"), ("token", [REDACTED]), ("host", "example.com"), ("token", [REDACTED])]"#
+        );
+        assert!(!format!("{:#?}", redacted).contains("\"a\""));
+    }
+
+    #[test]
+    fn display() {
+        let keylist = keylist();
+        let redacted = keylist.redact(|key| key.starts_with('t'));
+
+        assert_eq!(
+            redacted.to_string(),
+            r#"[user: "alice", token: "[REDACTED]", host: "example.com", token: "[REDACTED]"]"#
+        );
+        assert_eq!(
+            redacted.with_style(Style::KeyEquals).to_string(),
+            "user=alice token=[REDACTED] host=example.com token=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn nothing_secret() {
+        let keylist = keylist();
+
+        assert_eq!(
+            keylist.redact_keys(Vec::new()).to_string(),
+            keylist.to_string()
+        );
+    }
+
+    #[test]
+    fn hash_keylist() {
+        let keylist: HashKeylist<_, _, _> =
+            vec![("password", 1), ("port", 80), ("password", 2)].into();
+        let redacted = keylist.redact_keys(vec!["password"]);
+
+        assert_eq!(
+            format!("{:?}", redacted),
+            r#"[("password", [REDACTED]), ("port", 80), ("password", [REDACTED])]"#
+        );
+        assert_eq!(
+            redacted.with_style(Style::JsonScalars).to_string(),
+            r#"{"password":"[REDACTED]","port":80,"password":"[REDACTED]"}"#
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use crate::{HashKeylist, VecKeylist};
+    use serde_test::{assert_ser_tokens, Token};
+    use std::collections::hash_map::RandomState;
+
+    #[test]
+    fn serialize() {
+        let keylist = VecKeylist(vec![("user", "alice"), ("password", "hunter2")]);
+
+        assert_ser_tokens(
+            &keylist.redact_keys(vec!["password"]),
+            &[
+                Token::NewtypeStruct { name: "VecKeylist" },
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::Str("user"),
+                Token::Str("alice"),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::Str("password"),
+                Token::Str("[REDACTED]"),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn serialize_hash_keylist() {
+        let keylist: HashKeylist<_, _, RandomState> = vec![("token", 1), ("port", 80)].into();
+
+        assert_ser_tokens(
+            &keylist.redact_keys(vec!["token"]),
+            &[
+                Token::NewtypeStruct {
+                    name: "HashKeylist",
+                },
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::Str("token"),
+                Token::Str("[REDACTED]"),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::Str("port"),
+                Token::I32(80),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
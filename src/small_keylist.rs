@@ -0,0 +1,430 @@
+//! A keylist that stores up to `N` pairs inline and only allocates when it grows beyond that.
+//!
+//! Needs the `smallvec` feature. The api is the same as `VecKeylist`, the read-only and in-place methods come from
+//! `KeylistSlice` which it derefs to.
+//! ```
+//! use keylist::SmallKeylist;
+//!
+//! let mut keylist: SmallKeylist<&str, i32, 4> = SmallKeylist::new();
+//! keylist.push("a", 5);
+//! keylist.push("b", 2);
+//! keylist.push("a", 1);
+//!
+//! assert_eq!(keylist.get(&"a"), Some(&5));
+//! assert_eq!(keylist.get_all(&"a"), vec![&5, &1]);
+//! assert!(!keylist.spilled());
+//!
+//! keylist.extend(vec![("c", 3), ("d", 4)]);
+//! assert!(keylist.spilled());
+//! ```
+
+use crate::{KeylistSlice, VecKeylist};
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FromIterator;
+use core::ops::{Deref, DerefMut};
+use smallvec::SmallVec;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SmallKeylist<K, V, const N: usize>(pub SmallVec<[(K, V); N]>);
+
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<K: fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug for SmallKeylist<K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, const N: usize> Default for SmallKeylist<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> SmallKeylist<K, V, N> {
+    pub fn new() -> Self {
+        SmallKeylist(SmallVec::new())
+    }
+
+    /// True when the pairs no longer fit inline and are stored on the heap.
+    pub fn spilled(&self) -> bool {
+        self.0.spilled()
+    }
+
+    /// The number of pairs that fit inline, `N`.
+    pub fn inline_size(&self) -> usize {
+        self.0.inline_size()
+    }
+
+    pub fn into_swapped(self) -> SmallKeylist<V, K, N> {
+        SmallKeylist(self.0.into_iter().map(|(k, v)| (v, k)).collect())
+    }
+
+    pub fn insert(&mut self, index: usize, k: K, v: V) {
+        self.0.insert(index, (k, v))
+    }
+
+    pub fn push(&mut self, k: K, v: V) {
+        self.0.push((k, v))
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        self.0.pop()
+    }
+
+    pub fn remove(&mut self, index: usize) -> (K, V) {
+        self.0.remove(index)
+    }
+}
+
+impl<K, V, const N: usize> Deref for SmallKeylist<K, V, N> {
+    type Target = KeylistSlice<K, V>;
+
+    fn deref(&self) -> &KeylistSlice<K, V> {
+        KeylistSlice::new(&self.0)
+    }
+}
+
+impl<K, V, const N: usize> DerefMut for SmallKeylist<K, V, N> {
+    fn deref_mut(&mut self) -> &mut KeylistSlice<K, V> {
+        KeylistSlice::new_mut(&mut self.0)
+    }
+}
+
+impl<K, V, const N: usize> From<Vec<(K, V)>> for SmallKeylist<K, V, N> {
+    /// Moves the pairs inline if they fit, otherwise reuses the allocation.
+    fn from(list: Vec<(K, V)>) -> Self {
+        SmallKeylist(SmallVec::from_vec(list))
+    }
+}
+
+impl<K, V, const N: usize> From<VecKeylist<K, V>> for SmallKeylist<K, V, N> {
+    fn from(list: VecKeylist<K, V>) -> Self {
+        SmallKeylist::from(list.0)
+    }
+}
+
+impl<K, V, const N: usize, const M: usize> From<[(K, V); M]> for SmallKeylist<K, V, N> {
+    fn from(list: [(K, V); M]) -> Self {
+        SmallKeylist(IntoIterator::into_iter(list).collect())
+    }
+}
+
+impl<K, V, const N: usize> From<SmallKeylist<K, V, N>> for VecKeylist<K, V> {
+    fn from(list: SmallKeylist<K, V, N>) -> Self {
+        VecKeylist(list.0.into_vec())
+    }
+}
+
+impl<K, V, const N: usize> IntoIterator for SmallKeylist<K, V, N> {
+    type Item = (K, V);
+    type IntoIter = smallvec::IntoIter<[(K, V); N]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<K, V, const N: usize> Extend<(K, V)> for SmallKeylist<K, V, N> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'a, K: Copy, V: Copy, const N: usize> Extend<(&'a K, &'a V)> for SmallKeylist<K, V, N> {
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        self.0.extend(iter.into_iter().map(|(k, v)| (*k, *v)))
+    }
+}
+
+impl<K, V, const N: usize> FromIterator<(K, V)> for SmallKeylist<K, V, N> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        SmallKeylist(iter.into_iter().collect())
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, const N: usize> PartialEq<[(K, V)]> for SmallKeylist<K, V, N> {
+    fn eq(&self, other: &[(K, V)]) -> bool {
+        self.0[..] == *other
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, const N: usize> PartialEq<VecKeylist<K, V>>
+    for SmallKeylist<K, V, N>
+{
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, const N: usize> PartialEq<SmallKeylist<K, V, N>>
+    for VecKeylist<K, V>
+{
+    fn eq(&self, other: &SmallKeylist<K, V, N>) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use crate::SmallKeylist;
+    use core::marker::PhantomData;
+    use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+    use smallvec::SmallVec;
+
+    impl<K: Serialize, V: Serialize, const N: usize> Serialize for SmallKeylist<K, V, N> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_newtype_struct("SmallKeylist", &self.0[..])
+        }
+    }
+
+    struct KeylistVisitor<K, V, const N: usize> {
+        marker: PhantomData<fn() -> SmallKeylist<K, V, N>>,
+    }
+
+    impl<K, V, const N: usize> KeylistVisitor<K, V, N> {
+        fn new() -> Self {
+            KeylistVisitor {
+                marker: PhantomData,
+            }
+        }
+    }
+
+    impl<'de, K, V, const N: usize> Visitor<'de> for KeylistVisitor<K, V, N>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        type Value = SmallKeylist<K, V, N>;
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("Struct SmallKeylist")
+        }
+
+        fn visit_seq<X>(self, mut seq: X) -> Result<Self::Value, X::Error>
+        where
+            X: SeqAccess<'de>,
+        {
+            let mut buffer = SmallVec::new();
+
+            while let Some(x) = seq.next_element()? {
+                buffer.push(x)
+            }
+            Ok(SmallKeylist(buffer))
+        }
+
+        fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let mut buffer = SmallVec::new();
+
+            while let Some(x) = access.next_entry()? {
+                buffer.push(x)
+            }
+            Ok(SmallKeylist(buffer))
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_newtype_struct("SmallKeylist", KeylistVisitor::new())
+        }
+    }
+
+    impl<'de, K, V, const N: usize> Deserialize<'de> for SmallKeylist<K, V, N>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(KeylistVisitor::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SmallKeylist, VecKeylist};
+
+    type Small<K, V> = SmallKeylist<K, V, 4>;
+
+    #[test]
+    fn inline_until_full() {
+        let mut keylist: Small<_, _> = SmallKeylist::new();
+
+        assert_eq!(keylist.inline_size(), 4);
+        for i in 0..4 {
+            keylist.push(i, i * 2);
+        }
+        assert!(!keylist.spilled());
+
+        keylist.push(4, 8);
+        assert!(keylist.spilled());
+        assert_eq!(keylist.get(&4), Some(&8));
+    }
+
+    #[test]
+    fn get() {
+        let keylist: Small<_, _> = SmallKeylist::from(vec![("a", 5), ("b", 2), ("a", 1)]);
+
+        assert_eq!(keylist.get(&"a"), Some(&5));
+        assert_eq!(keylist.get_all(&"a"), vec![&5, &1]);
+        assert_eq!(keylist.get_key_value(&"b"), Some(&("b", 2)));
+        assert_eq!(keylist.find_by_value(&1), Some(&("a", 1)));
+        assert_eq!(keylist.keys_for_value(&2), vec![&"b"]);
+        assert!(keylist.contains(&("a", 1)));
+        assert_eq!(keylist.get(&"c"), None);
+    }
+
+    #[test]
+    fn mutate() {
+        let mut keylist: Small<_, _> = SmallKeylist::new();
+        keylist.push("a", 1);
+        keylist.push("b", 2);
+        keylist.insert(1, "c", 3);
+
+        *keylist.get_mut(&"b").unwrap() = 20;
+        for v in keylist.values_mut() {
+            *v += 1;
+        }
+        assert_eq!(keylist, VecKeylist(vec![("a", 2), ("c", 4), ("b", 21)]));
+        assert_eq!(keylist.remove(1), ("c", 4));
+        assert_eq!(keylist.pop(), Some(("b", 21)));
+        assert_eq!(keylist.len(), 1);
+        assert!(!keylist.is_empty());
+    }
+
+    #[test]
+    fn sort() {
+        let mut keylist: Small<_, _> =
+            SmallKeylist::from(vec![("b", 2), ("a", 4), ("b", 1), ("a", 3), ("c", 0)]);
+
+        keylist.sort_by_key();
+        assert_eq!(
+            keylist.keys().collect::<Vec<_>>(),
+            vec![&"a", &"a", &"b", &"b", &"c"]
+        );
+        assert_eq!(keylist.get_sorted(&"b"), Some(&2));
+
+        keylist.sort();
+        assert_eq!(keylist.get_sorted(&"a"), Some(&3));
+        assert_eq!(keylist.get_sorted(&"d"), None);
+
+        keylist.sort_by_value();
+        assert_eq!(
+            keylist.values().collect::<Vec<_>>(),
+            vec![&0, &1, &2, &3, &4]
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let pairs = vec![("a", 1), ("b", 2)];
+        let keylist: Small<_, _> = pairs.iter().cloned().collect();
+
+        assert_eq!(keylist, VecKeylist(pairs.clone()));
+        assert_eq!(VecKeylist(pairs.clone()), keylist);
+        assert_eq!(VecKeylist::from(keylist.clone()), VecKeylist(pairs.clone()));
+        assert!(keylist == pairs[..]);
+        assert_eq!(keylist.clone().into_iter().collect::<Vec<_>>(), pairs);
+        assert_eq!(
+            keylist.clone().into_swapped(),
+            SmallKeylist::<_, _, 4>::from(vec![(1, "a"), (2, "b")])
+        );
+        assert_eq!(format!("{:?}", keylist), r#"[("a", 1), ("b", 2)]"#);
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |x: &dyn Fn(&mut DefaultHasher)| {
+            let mut hasher = DefaultHasher::new();
+            x(&mut hasher);
+            hasher.finish()
+        };
+        let pairs = vec![("a", 1), ("b", 2)];
+        let keylist: Small<_, _> = SmallKeylist::from(pairs.clone());
+
+        assert_eq!(
+            hash(&|h| keylist.hash(h)),
+            hash(&|h| VecKeylist(pairs.clone()).hash(h))
+        );
+    }
+
+    #[test]
+    fn from_array() {
+        let keylist: Small<_, _> = SmallKeylist::from([("a", 1), ("b", 2)]);
+        assert!(!keylist.spilled());
+
+        let keylist: Small<_, _> = SmallKeylist::from([(1, 1), (2, 2), (3, 3), (4, 4), (5, 5)]);
+        assert!(keylist.spilled());
+        assert_eq!(keylist.get(&5), Some(&5));
+    }
+
+    #[test]
+    fn slice() {
+        let mut keylist: Small<_, _> = SmallKeylist::from(vec![("b", 2), ("a", 5), ("b", 1)]);
+
+        assert_eq!(keylist[1..], VecKeylist(vec![("a", 5), ("b", 1)]));
+        keylist[1..].sort_by_key();
+        assert_eq!(keylist, VecKeylist(vec![("b", 2), ("a", 5), ("b", 1)]));
+        assert_eq!(keylist[..].get_all(&"b"), vec![&2, &1]);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use crate::SmallKeylist;
+    use serde_test::{assert_de_tokens, assert_tokens, Token};
+
+    #[test]
+    fn serde_de_map() {
+        let expected: SmallKeylist<_, _, 2> = SmallKeylist::from(vec![("oke", 1), ("test", 15)]);
+
+        assert_de_tokens(
+            &expected,
+            &[
+                Token::Map { len: Some(2) },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::MapEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let input: SmallKeylist<_, _, 2> = SmallKeylist::from(vec![("oke", 1), ("test", 15)]);
+
+        assert_tokens(
+            &input,
+            &[
+                Token::NewtypeStruct {
+                    name: "SmallKeylist",
+                },
+                Token::Seq { len: Some(2) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("test"),
+                Token::I32(15),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
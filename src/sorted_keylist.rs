@@ -0,0 +1,325 @@
+//! A keylist that is always sorted by key.
+//!
+//! Pairs with equal keys stay in insertion order, so `get_first` returns the value that was inserted first.
+//! ```
+//! use keylist::SortedKeylist;
+//!
+//! let mut keylist = SortedKeylist::new();
+//! keylist.insert(3, "c");
+//! keylist.insert(1, "a");
+//! keylist.insert(3, "C");
+//! keylist.insert(2, "b");
+//!
+//! assert_eq!(keylist.get_first(&3), Some(&"c"));
+//! assert_eq!(keylist.get_all(&3), vec![&"c", &"C"]);
+//! assert_eq!(keylist.range(2..).to_vec(), vec![(2, "b"), (3, "c"), (3, "C")]);
+//! assert_eq!(keylist.lower_bound(&3), 2);
+//! assert_eq!(keylist.upper_bound(&3), 4);
+//! ```
+
+use crate::VecKeylist;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FromIterator;
+use core::ops::{Bound, RangeBounds};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortedKeylist<K: Ord, V>(Vec<(K, V)>);
+
+impl<K: Ord, V> Default for SortedKeylist<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> SortedKeylist<K, V> {
+    pub fn new() -> Self {
+        SortedKeylist(Vec::new())
+    }
+
+    /// Inserts the pair after the pairs with an equal key, returns its index.
+    pub fn insert(&mut self, key: K, value: V) -> usize {
+        let index = self.upper_bound(&key);
+        self.0.insert(index, (key, value));
+        index
+    }
+
+    /// Index of the first pair with a key that is not less than `key`.
+    pub fn lower_bound(&self, key: &K) -> usize {
+        self.0.partition_point(|(k, _)| k < key)
+    }
+
+    /// Index of the first pair with a key that is greater than `key`.
+    pub fn upper_bound(&self, key: &K) -> usize {
+        self.0.partition_point(|(k, _)| k <= key)
+    }
+
+    /// All pairs with the key.
+    pub fn equal_range(&self, key: &K) -> &[(K, V)] {
+        &self.0[self.lower_bound(key)..self.upper_bound(key)]
+    }
+
+    pub fn get_key_value(&self, key: &K) -> Option<&(K, V)> {
+        self.equal_range(key).first()
+    }
+
+    /// The value that was inserted first for the key.
+    pub fn get_first(&self, key: &K) -> Option<&V> {
+        let (_, v) = self.get_key_value(key)?;
+        Some(v)
+    }
+
+    pub fn get_first_mut(&mut self, key: &K) -> Option<&mut V> {
+        let index = self.lower_bound(key);
+        match self.0.get_mut(index) {
+            Some((k, v)) if k == key => Some(v),
+            _ => None,
+        }
+    }
+
+    /// get all values matching the key, in insertion order
+    pub fn get_all(&self, key: &K) -> Vec<&V> {
+        self.equal_range(key).iter().map(|(_, v)| v).collect()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get_key_value(key).is_some()
+    }
+
+    /// The pairs with a key in the range, an empty slice if the range is empty or reversed.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> &[(K, V)] {
+        let start = match range.start_bound() {
+            Bound::Included(key) => self.lower_bound(key),
+            Bound::Excluded(key) => self.upper_bound(key),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => self.upper_bound(key),
+            Bound::Excluded(key) => self.lower_bound(key),
+            Bound::Unbounded => self.0.len(),
+        };
+        if start < end {
+            &self.0[start..end]
+        } else {
+            &[]
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> (K, V) {
+        self.0.remove(index)
+    }
+
+    /// Removes all the values of the key, in insertion order.
+    pub fn remove_all(&mut self, key: &K) -> Vec<V> {
+        let (start, end) = (self.lower_bound(key), self.upper_bound(key));
+        self.0.drain(start..end).map(|(_, v)| v).collect()
+    }
+
+    /// Removes the pair with the largest key.
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        self.0.pop()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(K, V)> {
+        self.0.iter()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.0.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.0.iter().map(|(_, v)| v)
+    }
+
+    /// Only the values are mutable, changing the keys could break the order.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.0.iter_mut().map(|(_, v)| v)
+    }
+
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<K: Ord + fmt::Debug, V: fmt::Debug> fmt::Debug for SortedKeylist<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SortedKeylist<K, V> {
+    /// Appends the pairs and restores the order with a stable sort, so duplicates keep their insertion order.
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        self.0.extend(iter);
+        self.0.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SortedKeylist<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut keylist = SortedKeylist::new();
+        keylist.extend(iter);
+        keylist
+    }
+}
+
+impl<K: Ord, V> From<VecKeylist<K, V>> for SortedKeylist<K, V> {
+    fn from(input: VecKeylist<K, V>) -> Self {
+        let mut pairs = input.0;
+        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        SortedKeylist(pairs)
+    }
+}
+
+impl<K: Ord, V, const N: usize> From<[(K, V); N]> for SortedKeylist<K, V> {
+    fn from(input: [(K, V); N]) -> Self {
+        SortedKeylist::from(VecKeylist::from(input))
+    }
+}
+
+impl<K: Ord, V> From<SortedKeylist<K, V>> for VecKeylist<K, V> {
+    fn from(input: SortedKeylist<K, V>) -> Self {
+        VecKeylist(input.0)
+    }
+}
+
+impl<K: Ord, V> IntoIterator for SortedKeylist<K, V> {
+    type Item = (K, V);
+    type IntoIter = alloc::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SortedKeylist;
+    use crate::VecKeylist;
+    use std::ops::Bound;
+
+    fn data() -> SortedKeylist<i32, &'static str> {
+        vec![
+            (5, "e"),
+            (1, "a"),
+            (3, "c1"),
+            (3, "c2"),
+            (7, "g"),
+            (3, "c3"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn insert_keeps_order() {
+        let mut keylist = data();
+
+        assert_eq!(keylist.insert(3, "c4"), 4);
+        assert_eq!(keylist.insert(0, "z"), 0);
+        assert_eq!(keylist.insert(9, "i"), 8);
+
+        let keys: Vec<_> = keylist.keys().copied().collect();
+        assert_eq!(keys, vec![0, 1, 3, 3, 3, 3, 5, 7, 9]);
+        assert_eq!(keylist.get_all(&3), vec![&"c1", &"c2", &"c3", &"c4"]);
+    }
+
+    #[test]
+    fn lookups() {
+        let mut keylist = data();
+
+        assert_eq!(keylist.get_first(&3), Some(&"c1"));
+        assert_eq!(keylist.get_key_value(&7), Some(&(7, "g")));
+        assert_eq!(keylist.get_first(&4), None);
+        assert!(keylist.get_all(&4).is_empty());
+        assert!(keylist.contains_key(&1));
+        assert!(!keylist.contains_key(&8));
+
+        *keylist.get_first_mut(&3).unwrap() = "C";
+        assert_eq!(keylist.get_all(&3), vec![&"C", &"c2", &"c3"]);
+        assert_eq!(keylist.get_first_mut(&4), None);
+    }
+
+    #[test]
+    fn bounds() {
+        let keylist = data();
+
+        assert_eq!(keylist.lower_bound(&3), 1);
+        assert_eq!(keylist.upper_bound(&3), 4);
+        assert_eq!(keylist.lower_bound(&4), 4);
+        assert_eq!(keylist.upper_bound(&4), 4);
+        assert_eq!(keylist.lower_bound(&0), 0);
+        assert_eq!(keylist.upper_bound(&10), 6);
+        assert_eq!(keylist.equal_range(&3).len(), 3);
+    }
+
+    #[test]
+    fn range() {
+        let keylist = data();
+        let keys = |slice: &[(i32, &str)]| slice.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+
+        assert_eq!(keys(keylist.range(3..7)), vec![3, 3, 3, 5]);
+        assert_eq!(keys(keylist.range(3..=7)), vec![3, 3, 3, 5, 7]);
+        assert_eq!(keys(keylist.range(..3)), vec![1]);
+        assert_eq!(keys(keylist.range(4..)), vec![5, 7]);
+        assert_eq!(keys(keylist.range(..)), vec![1, 3, 3, 3, 5, 7]);
+        assert_eq!(
+            keys(keylist.range((Bound::Excluded(3), Bound::Included(5)))),
+            vec![5]
+        );
+        assert!(keylist.range(4..5).is_empty());
+        assert!(keylist
+            .range((Bound::Included(7), Bound::Excluded(3)))
+            .is_empty());
+    }
+
+    #[test]
+    fn remove() {
+        let mut keylist = data();
+
+        assert_eq!(keylist.remove_all(&3), vec!["c1", "c2", "c3"]);
+        assert!(keylist.remove_all(&3).is_empty());
+        assert_eq!(keylist.remove(0), (1, "a"));
+        assert_eq!(keylist.pop(), Some((7, "g")));
+        assert_eq!(keylist.as_slice(), &[(5, "e")]);
+    }
+
+    #[test]
+    fn conversions() {
+        let keylist = SortedKeylist::from(VecKeylist(vec![("b", 1), ("a", 2), ("b", 0)]));
+
+        assert_eq!(keylist.as_slice(), &[("a", 2), ("b", 1), ("b", 0)]);
+        assert_eq!(
+            VecKeylist::from(keylist.clone()),
+            VecKeylist(vec![("a", 2), ("b", 1), ("b", 0)])
+        );
+        assert_eq!(
+            format!("{:?}", keylist),
+            r#"[("a", 2), ("b", 1), ("b", 0)]"#
+        );
+
+        let mut extended = keylist;
+        extended.extend(vec![("a", 9), ("c", 3)]);
+        assert_eq!(
+            extended.into_iter().collect::<Vec<_>>(),
+            vec![("a", 2), ("a", 9), ("b", 1), ("b", 0), ("c", 3)]
+        );
+    }
+
+    #[test]
+    fn from_array() {
+        let keylist = SortedKeylist::from([(3, "c"), (1, "a"), (3, "b")]);
+
+        assert_eq!(keylist.as_slice(), &[(1, "a"), (3, "c"), (3, "b")]);
+    }
+}
@@ -0,0 +1,296 @@
+//! A keylist over a `'static` slice that can be declared `const` or `static`, no `lazy_static` needed.
+//!
+//! Lookups are linear, tables declared sorted with `StaticKeylist::sorted` can also be searched with binary search
+//! by the `*_sorted` lookups.
+//! Everything else comes from `KeylistSlice`, which it derefs to.
+//! ```
+//! use keylist::{StaticKeylist, VecKeylist};
+//!
+//! static DEFAULTS: StaticKeylist<&str, u16> = StaticKeylist::new(&[("port", 80), ("timeout", 30), ("port", 8080)]);
+//! const PORTS: StaticKeylist<&str, u16> = StaticKeylist::sorted(&[("http", 80), ("https", 443), ("ssh", 22)]);
+//!
+//! assert_eq!(DEFAULTS.get(&"port"), Some(&80));
+//! assert_eq!(DEFAULTS.get_all(&"port"), vec![&80, &8080]);
+//! assert_eq!(PORTS.get_sorted(&"https"), Some(&443));
+//!
+//! let mut options = VecKeylist::from(DEFAULTS);
+//! options.push("retries", 3);
+//! assert_eq!(options.len(), 4);
+//! ```
+
+use crate::{KeylistSlice, VecKeylist};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::slice;
+
+pub struct StaticKeylist<K: 'static, V: 'static> {
+    pairs: &'static [(K, V)],
+    sorted: bool,
+}
+
+impl<K, V> StaticKeylist<K, V> {
+    pub const fn new(pairs: &'static [(K, V)]) -> Self {
+        StaticKeylist {
+            pairs,
+            sorted: false,
+        }
+    }
+
+    /// The pairs have to be sorted by key, the `*_sorted` lookups use binary search and give unspecified results otherwise.
+    pub const fn sorted(pairs: &'static [(K, V)]) -> Self {
+        StaticKeylist {
+            pairs,
+            sorted: true,
+        }
+    }
+
+    pub const fn as_slice(&self) -> &'static [(K, V)] {
+        self.pairs
+    }
+
+    pub const fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub const fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    pub fn iter(&self) -> slice::Iter<'static, (K, V)> {
+        self.pairs.iter()
+    }
+}
+
+impl<K: PartialEq, V> StaticKeylist<K, V> {
+    pub fn get_key_value(&self, key: &K) -> Option<&'static (K, V)> {
+        self.pairs.iter().find(|(k, _)| k == key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&'static V> {
+        let (_, v) = self.get_key_value(key)?;
+        Some(v)
+    }
+
+    /// get all values matching the key
+    pub fn get_all(&self, key: &K) -> Vec<&'static V> {
+        self.pairs
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get_key_value(key).is_some()
+    }
+}
+
+impl<K: Ord, V> StaticKeylist<K, V> {
+    /// Uses binary search if the table is declared with `StaticKeylist::sorted`, falls back to `get_key_value` otherwise.
+    pub fn get_key_value_sorted(&self, key: &K) -> Option<&'static (K, V)> {
+        if !self.sorted {
+            return self.get_key_value(key);
+        }
+        let index = self.pairs.partition_point(|(k, _)| k < key);
+        self.pairs.get(index).filter(|(k, _)| k == key)
+    }
+
+    /// Same as `get_key_value_sorted`, but only the value.
+    pub fn get_sorted(&self, key: &K) -> Option<&'static V> {
+        let (_, v) = self.get_key_value_sorted(key)?;
+        Some(v)
+    }
+
+    /// Uses binary search if the table is declared with `StaticKeylist::sorted`, falls back to `get_all` otherwise.
+    pub fn get_all_sorted(&self, key: &K) -> Vec<&'static V> {
+        if !self.sorted {
+            return self.get_all(key);
+        }
+        let start = self.pairs.partition_point(|(k, _)| k < key);
+        self.pairs[start..]
+            .iter()
+            .take_while(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    pub fn contains_key_sorted(&self, key: &K) -> bool {
+        self.get_key_value_sorted(key).is_some()
+    }
+}
+
+impl<K, V> Deref for StaticKeylist<K, V> {
+    type Target = KeylistSlice<K, V>;
+
+    fn deref(&self) -> &KeylistSlice<K, V> {
+        KeylistSlice::new(self.pairs)
+    }
+}
+
+impl<K, V> Clone for StaticKeylist<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for StaticKeylist<K, V> {}
+
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for StaticKeylist<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Compares the pairs, whether the table was declared sorted does not matter.
+impl<K: PartialEq, V: PartialEq> PartialEq for StaticKeylist<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pairs == other.pairs
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for StaticKeylist<K, V> {}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<VecKeylist<K, V>> for StaticKeylist<K, V> {
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        *self.pairs == other.0[..]
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<StaticKeylist<K, V>> for VecKeylist<K, V> {
+    fn eq(&self, other: &StaticKeylist<K, V>) -> bool {
+        self.0[..] == *other.pairs
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for StaticKeylist<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pairs.hash(state);
+    }
+}
+
+impl<K, V> IntoIterator for StaticKeylist<K, V> {
+    type Item = &'static (K, V);
+    type IntoIter = slice::Iter<'static, (K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pairs.iter()
+    }
+}
+
+impl<K: Clone, V: Clone> From<StaticKeylist<K, V>> for VecKeylist<K, V> {
+    fn from(list: StaticKeylist<K, V>) -> Self {
+        VecKeylist(list.pairs.to_vec())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: serde::Serialize, V: serde::Serialize> serde::Serialize for StaticKeylist<K, V> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct("StaticKeylist", self.pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{StaticKeylist, VecKeylist};
+
+    static UNSORTED: StaticKeylist<&str, i32> =
+        StaticKeylist::new(&[("b", 2), ("a", 1), ("b", 3), ("c", 4)]);
+    const SORTED: StaticKeylist<&str, i32> =
+        StaticKeylist::sorted(&[("a", 1), ("b", 2), ("b", 3), ("c", 4)]);
+
+    #[test]
+    fn linear_lookup() {
+        assert!(!UNSORTED.is_sorted());
+        assert_eq!(UNSORTED.get(&"b"), Some(&2));
+        assert_eq!(UNSORTED.get_all(&"b"), vec![&2, &3]);
+        assert_eq!(UNSORTED.get_key_value(&"c"), Some(&("c", 4)));
+        assert_eq!(UNSORTED.get(&"z"), None);
+        assert!(UNSORTED.get_all(&"z").is_empty());
+    }
+
+    #[test]
+    fn binary_search_lookup() {
+        assert!(SORTED.is_sorted());
+        assert_eq!(SORTED.get_sorted(&"b"), Some(&2));
+        assert_eq!(SORTED.get_all_sorted(&"b"), vec![&2, &3]);
+        assert_eq!(SORTED.get_sorted(&"a"), Some(&1));
+        assert_eq!(SORTED.get_key_value_sorted(&"c"), Some(&("c", 4)));
+        assert!(SORTED.contains_key_sorted(&"c"));
+        assert!(!SORTED.contains_key_sorted(&"0"));
+        assert!(SORTED.get_all_sorted(&"bb").is_empty());
+        assert_eq!(SORTED.get_all(&"b"), vec![&2, &3]);
+
+        // not declared sorted, so these fall back to the linear lookup
+        assert_eq!(UNSORTED.get_sorted(&"a"), Some(&1));
+        assert_eq!(UNSORTED.get_all_sorted(&"b"), vec![&2, &3]);
+    }
+
+    #[test]
+    fn slice_methods() {
+        assert_eq!(UNSORTED.len(), 4);
+        assert_eq!(UNSORTED.find_by_value(&3), Some(&("b", 3)));
+        assert_eq!(UNSORTED[1..3].get(&"b"), Some(&3));
+        assert_eq!(
+            SORTED.keys().copied().collect::<Vec<_>>(),
+            vec!["a", "b", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn non_ord_keys() {
+        static FLOATS: StaticKeylist<f64, &str> =
+            StaticKeylist::new(&[(0.5, "half"), (1.0, "one")]);
+
+        assert_eq!(FLOATS.get(&1.0), Some(&"one"));
+        assert_eq!(FLOATS.get_all(&0.5), vec![&"half"]);
+    }
+
+    #[test]
+    fn to_owned() {
+        let keylist = VecKeylist::from(UNSORTED);
+
+        assert_eq!(keylist, UNSORTED);
+        assert_ne!(keylist, SORTED);
+        assert_eq!(
+            format!("{:?}", SORTED),
+            r#"[("a", 1), ("b", 2), ("b", 3), ("c", 4)]"#
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use crate::StaticKeylist;
+    use serde_test::{assert_ser_tokens, Token};
+
+    #[test]
+    fn serde_ser() {
+        static KEYLIST: StaticKeylist<&str, i32> = StaticKeylist::new(&[("oke", 1)]);
+
+        assert_ser_tokens(
+            &KEYLIST,
+            &[
+                Token::NewtypeStruct {
+                    name: "StaticKeylist",
+                },
+                Token::Seq { len: Some(1) },
+                Token::Tuple { len: 2 },
+                Token::BorrowedStr("oke"),
+                Token::I32(1),
+                Token::TupleEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
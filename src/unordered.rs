@@ -0,0 +1,342 @@
+//! Equality that ignores the order of the pairs, like Elixir's `Keyword.equal?`.
+//!
+//! `eq_unordered` compares the pairs as a multiset. `eq_per_key_order` also requires the values of
+//! each key to be in the same order, but ignores how the keys are interleaved.
+//! ```
+//! use keylist::unordered::Unordered;
+//! use keylist::VecKeylist;
+//! use std::collections::HashSet;
+//!
+//! let a = VecKeylist(vec![("a", 1), ("b", 2), ("a", 3)]);
+//! let b = VecKeylist(vec![("b", 2), ("a", 1), ("a", 3)]);
+//! let c = VecKeylist(vec![("a", 3), ("b", 2), ("a", 1)]);
+//!
+//! assert!(a.eq_per_key_order(&b));
+//! assert!(!a.eq_per_key_order(&c));
+//! assert!(a.eq_unordered(&c));
+//!
+//! let mut cache = HashSet::new();
+//! cache.insert(Unordered(a));
+//! assert!(cache.contains(&Unordered(c)));
+//! ```
+
+use crate::{HashKeylist, VecKeylist};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// Compares and hashes the wrapped keylist with `eq_unordered`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unordered<T>(pub T);
+
+/// Compares and hashes the wrapped keylist with `eq_per_key_order`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PerKeyOrder<T>(pub T);
+
+fn group<'a, K, V, I>(pairs: I) -> HashMap<&'a K, Vec<&'a V>>
+where
+    K: Hash + Eq + 'a,
+    V: 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    let mut map: HashMap<_, Vec<_>> = HashMap::new();
+    for (k, v) in pairs {
+        map.entry(k).or_default().push(v);
+    }
+    map
+}
+
+fn multiset_eq<V: PartialEq>(left: &[&V], right: &[&V]) -> bool {
+    let mut used = vec![false; right.len()];
+    left.len() == right.len()
+        && left.iter().all(|x| {
+            let found = right
+                .iter()
+                .zip(used.iter_mut())
+                .find(|(y, used)| !**used && x == *y);
+            match found {
+                Some((_, used)) => {
+                    *used = true;
+                    true
+                }
+                None => false,
+            }
+        })
+}
+
+fn grouped_eq<'a, K, V, I, J, F>(left: I, right: J, values_eq: F) -> bool
+where
+    K: Hash + Eq + 'a,
+    V: 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+    J: Iterator<Item = (&'a K, &'a V)>,
+    F: Fn(&[&V], &[&V]) -> bool,
+{
+    let left = group(left);
+    let right = group(right);
+    left.len() == right.len()
+        && left
+            .iter()
+            .all(|(k, x)| right.get(k).is_some_and(|y| values_eq(x, y)))
+}
+
+fn hash_one<T: Hash>(value: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Sum of the hashes of the pairs, so the order does not matter.
+fn hash_unordered<'a, K, V, I, H>(pairs: I, state: &mut H)
+where
+    K: Hash + 'a,
+    V: Hash + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+    H: Hasher,
+{
+    let (len, sum) = pairs.fold((0usize, 0u64), |(len, sum), pair| {
+        (len + 1, sum.wrapping_add(hash_one(pair)))
+    });
+    state.write_usize(len);
+    state.write_u64(sum);
+}
+
+/// Sum of the hashes of each key with its values in order.
+fn hash_per_key<'a, K, V, I, H>(pairs: I, state: &mut H)
+where
+    K: Hash + Eq + 'a,
+    V: Hash + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+    H: Hasher,
+{
+    let groups = group(pairs);
+    let sum = groups
+        .iter()
+        .fold(0u64, |sum, group| sum.wrapping_add(hash_one(group)));
+    state.write_usize(groups.len());
+    state.write_u64(sum);
+}
+
+impl<K: Hash + Eq, V: PartialEq> VecKeylist<K, V> {
+    /// True when both contain the same pairs, in any order.
+    pub fn eq_unordered(&self, other: &Self) -> bool {
+        self.len() == other.len() && grouped_eq(pairs(self), pairs(other), multiset_eq)
+    }
+
+    /// True when both have the same values for every key in the same order, the keys may be interleaved differently.
+    pub fn eq_per_key_order(&self, other: &Self) -> bool {
+        self.len() == other.len() && grouped_eq(pairs(self), pairs(other), |x, y| x == y)
+    }
+}
+
+impl<K, V, S> HashKeylist<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    /// True when both contain the same pairs, in any order.
+    pub fn eq_unordered(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.data.len() == other.data.len()
+            && self.data.iter().all(|(k, x)| {
+                other.data.get(k).is_some_and(|y| {
+                    multiset_eq(&x.iter().collect::<Vec<_>>(), &y.iter().collect::<Vec<_>>())
+                })
+            })
+    }
+
+    /// True when both have the same values for every key in the same order, the keys may be interleaved differently.
+    pub fn eq_per_key_order(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+fn pairs<K, V>(keylist: &VecKeylist<K, V>) -> impl Iterator<Item = (&K, &V)> {
+    keylist.iter().map(|(k, v)| (k, v))
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for Unordered<VecKeylist<K, V>> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_unordered(&other.0)
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for Unordered<VecKeylist<K, V>> {}
+
+impl<K: Hash + Eq, V: Hash> Hash for Unordered<VecKeylist<K, V>> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_unordered(pairs(&self.0), state)
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for PerKeyOrder<VecKeylist<K, V>> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_per_key_order(&other.0)
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for PerKeyOrder<VecKeylist<K, V>> {}
+
+impl<K: Hash + Eq, V: Hash> Hash for PerKeyOrder<VecKeylist<K, V>> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_per_key(pairs(&self.0), state)
+    }
+}
+
+impl<K, V, S> PartialEq for Unordered<HashKeylist<K, V, S>>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_unordered(&other.0)
+    }
+}
+
+impl<K, V, S> Eq for Unordered<HashKeylist<K, V, S>>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K, V, S> Hash for Unordered<HashKeylist<K, V, S>>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_unordered(self.0.iter(), state)
+    }
+}
+
+impl<K, V, S> PartialEq for PerKeyOrder<HashKeylist<K, V, S>>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_per_key_order(&other.0)
+    }
+}
+
+impl<K, V, S> Eq for PerKeyOrder<HashKeylist<K, V, S>>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K, V, S> Hash for PerKeyOrder<HashKeylist<K, V, S>>
+where
+    K: Hash + Eq,
+    V: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_per_key(self.0.iter(), state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_one, PerKeyOrder, Unordered};
+    use crate::{HashKeylist, VecKeylist};
+
+    fn keylists() -> Vec<VecKeylist<&'static str, i32>> {
+        vec![
+            VecKeylist(vec![("a", 1), ("b", 2), ("a", 3)]),
+            VecKeylist(vec![("b", 2), ("a", 1), ("a", 3)]),
+            VecKeylist(vec![("a", 3), ("b", 2), ("a", 1)]),
+            VecKeylist(vec![("a", 1), ("b", 2), ("a", 1)]),
+            VecKeylist(vec![("a", 1), ("b", 2)]),
+        ]
+    }
+
+    #[test]
+    fn vec_keylist() {
+        let k = keylists();
+
+        assert!(k[0].eq_unordered(&k[1]));
+        assert!(k[0].eq_unordered(&k[2]));
+        assert!(!k[0].eq_unordered(&k[3]));
+        assert!(!k[0].eq_unordered(&k[4]));
+        assert!(!k[3].eq_unordered(&k[0]));
+
+        assert!(k[0].eq_per_key_order(&k[1]));
+        assert!(!k[0].eq_per_key_order(&k[2]));
+        assert!(!k[0].eq_per_key_order(&k[3]));
+        assert!(!k[0].eq_per_key_order(&k[4]));
+
+        assert!(VecKeylist::<u8, u8>::new().eq_unordered(&VecKeylist::new()));
+    }
+
+    #[test]
+    fn hash_keylist() {
+        let k: Vec<HashKeylist<_, _, _>> = keylists()
+            .into_iter()
+            .map(|x| HashKeylist::from(x.0))
+            .collect();
+
+        assert!(k[0].eq_unordered(&k[1]));
+        assert!(k[0].eq_unordered(&k[2]));
+        assert!(!k[0].eq_unordered(&k[3]));
+        assert!(!k[0].eq_unordered(&k[4]));
+
+        assert!(k[0].eq_per_key_order(&k[1]));
+        assert!(!k[0].eq_per_key_order(&k[2]));
+        assert!(!k[0].eq_per_key_order(&k[3]));
+    }
+
+    #[test]
+    fn wrappers_hash_consistently() {
+        let k = keylists();
+
+        for (i, x) in k.iter().enumerate() {
+            for y in &k[i..] {
+                let (x, y) = (x.clone(), y.clone());
+                if x.eq_unordered(&y) {
+                    assert!(Unordered(x.clone()) == Unordered(y.clone()));
+                    assert_eq!(
+                        hash_one(Unordered(x.clone())),
+                        hash_one(Unordered(y.clone()))
+                    );
+                    let (hx, hy) = (HashKeylist::from(x.0), HashKeylist::from(y.0));
+                    assert_eq!(hash_one(Unordered(hx)), hash_one(Unordered(hy)));
+                } else {
+                    assert!(Unordered(x) != Unordered(y));
+                }
+            }
+        }
+
+        assert_eq!(
+            hash_one(PerKeyOrder(k[0].clone())),
+            hash_one(PerKeyOrder(k[1].clone()))
+        );
+        assert_ne!(
+            hash_one(PerKeyOrder(k[0].clone())),
+            hash_one(PerKeyOrder(k[2].clone()))
+        );
+        assert_eq!(
+            hash_one(PerKeyOrder(HashKeylist::from(k[0].0.clone()))),
+            hash_one(PerKeyOrder(k[1].clone()))
+        );
+    }
+
+    #[test]
+    fn hash_set() {
+        use std::collections::HashSet;
+
+        let set: HashSet<_> = keylists().into_iter().map(Unordered).collect();
+        assert_eq!(set.len(), 3);
+
+        let set: HashSet<_> = keylists().into_iter().map(PerKeyOrder).collect();
+        assert_eq!(set.len(), 4);
+    }
+}
@@ -1,8 +1,25 @@
-use std::hash::Hash;
+use crate::KeylistSlice;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+use core::ops::{Deref, DerefMut};
 
-#[derive(Debug, PartialEq)]
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
 pub struct VecKeylist<K, V>(pub Vec<(K, V)>);
 
+/// Formats as a list of pairs: `[("a", 1), ("b", 2)]`.
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for VecKeylist<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> Default for VecKeylist<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<K, V> VecKeylist<K, V> {
     pub fn new() -> Self {
         VecKeylist(Vec::new())
@@ -27,69 +44,19 @@ impl<K, V> VecKeylist<K, V> {
     pub fn remove(&mut self, index: usize) -> (K, V) {
         self.0.remove(index)
     }
-
-    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a (K, V)> {
-        self.0.iter()
-    }
-
-    pub fn iter_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut (K, V)> {
-        self.0.iter_mut()
-    }
-
-    pub fn keys<'a>(&'a self) -> impl Iterator<Item = &'a K> {
-        self.iter().map(|(k, _)| k)
-    }
-
-    pub fn keys_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut K> {
-        self.iter_mut().map(|(k, _)| k)
-    }
-
-    pub fn values<'a>(&'a self) -> impl Iterator<Item = &'a V> {
-        self.iter().map(|(_, v)| v)
-    }
-
-    pub fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut V> {
-        self.iter_mut().map(|(_, v)| v)
-    }
-
-    pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
-    }
-
-    pub fn len(&self) -> usize {
-        self.0.len()
-    }
 }
 
-impl<K: PartialEq, V> VecKeylist<K, V> {
-    pub fn get_key_value(&self, key: &K) -> Option<&(K, V)> {
-        self.iter().find(|x| &x.0 == key)
-    }
-
-    pub fn get_key_value_mut(&mut self, key: &K) -> Option<&mut (K, V)> {
-        self.iter_mut().find(|x| &x.0 == key)
-    }
+impl<K, V> Deref for VecKeylist<K, V> {
+    type Target = KeylistSlice<K, V>;
 
-    pub fn get(&self, key: &K) -> Option<&V> {
-        let (_, v) = self.get_key_value(key)?;
-        Some(v)
-    }
-
-    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        let (_, v) = self.get_key_value_mut(key)?;
-        Some(v)
-    }
-
-    pub fn get_all_get_key_value(&self, key: &K) -> Vec<&(K, V)> {
-        self.iter().filter(|(k, _)| k == key).collect()
+    fn deref(&self) -> &KeylistSlice<K, V> {
+        KeylistSlice::new(&self.0)
     }
+}
 
-    /// get all values matching the key
-    pub fn get_all(&self, key: &K) -> Vec<&V> {
-        self.iter()
-            .filter(|(k, _)| k == key)
-            .map(|(_, v)| v)
-            .collect()
+impl<K, V> DerefMut for VecKeylist<K, V> {
+    fn deref_mut(&mut self) -> &mut KeylistSlice<K, V> {
+        KeylistSlice::new_mut(&mut self.0)
     }
 }
 
@@ -99,51 +66,19 @@ impl<K, V> From<Vec<(K, V)>> for VecKeylist<K, V> {
     }
 }
 
-impl<K: Clone, V: Clone> Clone for VecKeylist<K, V> {
-    fn clone(&self) -> Self {
-        VecKeylist(self.0.clone())
-    }
-}
-
-impl<K: std::cmp::Ord, V> VecKeylist<K, V> {
-    pub fn sort_by_key<'a>(&'a mut self) {
-        self.0.sort_by(|a, b| a.0.cmp(&b.0))
-    }
-}
-
-impl<K, V: std::cmp::Ord> VecKeylist<K, V> {
-    pub fn sort_by_value<'a>(&'a mut self) {
-        self.0.sort_by(|a, b| a.1.cmp(&b.1))
+impl<K, V, const N: usize> From<[(K, V); N]> for VecKeylist<K, V> {
+    fn from(list: [(K, V); N]) -> Self {
+        VecKeylist(Vec::from(list))
     }
 }
 
-impl<K: std::cmp::PartialEq, V: std::cmp::PartialEq> VecKeylist<K, V> {
-    pub fn contains(&self, item: &(K, V)) -> bool {
-        self.0.contains(item)
-    }
-}
-
-impl<K: std::cmp::Ord, V: std::cmp::Ord> VecKeylist<K, V> {
-    pub fn sort(&mut self) {
-        self.0.sort()
-    }
-
-    /// The normal get function uses a find on a iterator to find the key value.
-    /// This function uses binary search to find the key value
-    pub fn get_key_value_sorted(&self, key: &K) -> Option<&(K, V)> {
-        let index = self.0.binary_search_by_key(&key, |(a, _)| a).ok()?;
-        self.0.get(index)
-    }
-
-    /// The normal get function uses a find on a iterator to find the value.
-    /// This function uses binary search to find the value
-    pub fn get_sorted(&self, key: &K) -> Option<&V> {
-        let (_, v) = self.get_key_value_sorted(key)?;
-        Some(v)
+impl<K: Clone, V: Clone> Clone for VecKeylist<K, V> {
+    fn clone(&self) -> Self {
+        VecKeylist(self.0.clone())
     }
 }
 
-use std::vec::IntoIter;
+use alloc::vec::IntoIter;
 
 impl<K, V> IntoIterator for VecKeylist<K, V> {
     type Item = (K, V);
@@ -166,14 +101,38 @@ impl<'a, K: Copy, V: Copy> Extend<(&'a K, &'a V)> for VecKeylist<K, V> {
     }
 }
 
-impl<K, V> std::iter::FromIterator<(K, V)> for VecKeylist<K, V> {
+impl<K, V> core::iter::FromIterator<(K, V)> for VecKeylist<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> VecKeylist<K, V> {
         VecKeylist(iter.into_iter().collect())
     }
 }
 
+impl<K: PartialEq, V: PartialEq> PartialEq<[(K, V)]> for VecKeylist<K, V> {
+    fn eq(&self, other: &[(K, V)]) -> bool {
+        self.0 == other
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<Vec<(K, V)>> for VecKeylist<K, V> {
+    fn eq(&self, other: &Vec<(K, V)>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<VecKeylist<K, V>> for [(K, V)] {
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        *self == other.0[..]
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq<VecKeylist<K, V>> for Vec<(K, V)> {
+    fn eq(&self, other: &VecKeylist<K, V>) -> bool {
+        *self == other.0
+    }
+}
+
 impl<K: Hash, V: Hash> Hash for VecKeylist<K, V> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.0.hash(state);
     }
 }
@@ -181,9 +140,10 @@ impl<K: Hash, V: Hash> Hash for VecKeylist<K, V> {
 #[cfg(feature = "serde")]
 mod serde {
     use crate::VecKeylist;
+    use alloc::vec::Vec;
+    use core::marker::PhantomData;
     use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
     use serde::ser::{Serialize, Serializer};
-    use std::marker::PhantomData;
 
     impl<K: Serialize, V: Serialize> Serialize for VecKeylist<K, V> {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -212,7 +172,7 @@ mod serde {
         V: Deserialize<'de>,
     {
         type Value = VecKeylist<K, V>;
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
             formatter.write_str("Struct VecKeylist")
         }
 
@@ -281,13 +241,81 @@ mod tests {
         assert_eq!(keylist, expected);
     }
 
+    #[test]
+    fn debug() {
+        let keylist = VecKeylist(vec![("a", 1), ("b", 2)]);
+
+        assert_eq!(format!("{:?}", keylist), r#"[("a", 1), ("b", 2)]"#);
+        assert_eq!(
+            format!("{:#?}", keylist),
+            "[\n    (\n        \"a\",\n        1,\n    ),\n    (\n        \"b\",\n        2,\n    ),\n]"
+        );
+    }
+
+    #[test]
+    fn ord() {
+        use std::collections::BTreeSet;
+
+        let set: BTreeSet<_> = vec![
+            VecKeylist(vec![("b", 1)]),
+            VecKeylist(vec![("a", 2), ("b", 1)]),
+            VecKeylist(vec![("a", 1), ("c", 1)]),
+            VecKeylist(vec![("a", 2)]),
+            VecKeylist(vec![("b", 1)]),
+        ]
+        .into_iter()
+        .collect();
+
+        let expected = vec![
+            VecKeylist(vec![("a", 1), ("c", 1)]),
+            VecKeylist(vec![("a", 2)]),
+            VecKeylist(vec![("a", 2), ("b", 1)]),
+            VecKeylist(vec![("b", 1)]),
+        ];
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn eq_vec_and_slice() {
+        let keylist = VecKeylist(vec![("a", 1), ("b", 2)]);
+        let pairs = vec![("a", 1), ("b", 2)];
+
+        assert!(keylist == pairs);
+        assert!(pairs == keylist);
+        assert!(keylist == pairs[..]);
+        assert!(pairs[..] == keylist);
+        assert!(keylist != pairs[..1]);
+        assert!(keylist != vec![("b", 2), ("a", 1)]);
+    }
+
+    #[test]
+    fn default_in_derive() {
+        #[derive(Default, Debug, PartialEq, Eq, Hash)]
+        struct Config {
+            options: VecKeylist<String, String>,
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        seen.insert(Config::default());
+
+        assert!(seen.contains(&Config {
+            options: VecKeylist::new()
+        }));
+    }
+
+    #[test]
+    fn by_value() {
+        let keylist = VecKeylist(vec![("a", 1), ("b", 2), ("c", 1)]);
+
+        assert_eq!(keylist.find_by_value(&1), Some(&("a", 1)));
+        assert_eq!(keylist.find_by_value(&3), None);
+        assert_eq!(keylist.keys_for_value(&1), vec![&"a", &"c"]);
+        assert!(keylist.keys_for_value(&3).is_empty());
+    }
+
     #[test]
     fn sort() {
-        let mut map = Vec::new();
-        map.push(("a", 4));
-        map.push(("c", 3));
-        map.push(("b", 2));
-        map.push(("d", 1));
+        let map = vec![("a", 4), ("c", 3), ("b", 2), ("d", 1)];
 
         let mut keylist = VecKeylist::from_iter(map);
         keylist.sort();
@@ -300,9 +328,7 @@ mod tests {
     #[test]
     fn into_swapped() {
         let expected = VecKeylist(vec![(4, "a"), (3, "c")]);
-        let mut map = Vec::new();
-        map.push(("a", 4));
-        map.push(("c", 3));
+        let map = vec![("a", 4), ("c", 3)];
 
         let keylist = VecKeylist::from_iter(map).into_swapped();
 
@@ -343,6 +369,8 @@ mod tests {
 
         assert_eq!(keylist.get_sorted(&"b"), Some(&2));
         assert_eq!(keylist.get_sorted(&"f"), None);
+        assert_eq!(keylist.get_sorted(&"a"), Some(&4));
+        assert_eq!(keylist.get_key_value_sorted(&"0"), None);
     }
 
     #[test]
@@ -356,6 +384,13 @@ mod tests {
         keylist.hash(&mut hasher);
         assert_eq!(145292038701700647, hasher.finish())
     }
+
+    #[test]
+    fn from_array() {
+        let keylist = VecKeylist::from([("a", 1), ("b", 2)]);
+
+        assert_eq!(keylist, vec![("a", 1), ("b", 2)]);
+    }
 }
 
 #[cfg(all(test, feature = "serde"))]
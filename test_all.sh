@@ -1,2 +1,5 @@
+cargo build --no-default-features
+cargo build --no-default-features --features heapless
+cargo build --no-default-features --features heapless,serde
 cargo install cargo-all-features
 cargo test-all-features
\ No newline at end of file